@@ -0,0 +1,134 @@
+use crate::{board::Board, color::Color, piece::PieceType, square::Square};
+
+/// Squares attacked by each color in a position, computed once so that repeated queries
+/// don't have to scan the board again.
+///
+/// Bitboards use the bit of [`Square::to_index`], so bit 0 is a1 and bit 63 is h8.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttackMap {
+    attacked: [u64; 2],
+    attacker_counts: [[u8; 64]; 2],
+}
+
+impl AttackMap {
+    /// Bitboard of the squares attacked by at least one piece of the given color.
+    pub fn attacked_squares(&self, by: Color) -> u64 {
+        self.attacked[color_index(by)]
+    }
+
+    /// Whether any piece of the given color attacks the square.
+    pub fn is_attacked(&self, square: impl Into<Square>, by: Color) -> bool {
+        self.attacker_count(square, by) > 0
+    }
+
+    /// Number of pieces of the given color attacking the square.
+    pub fn attacker_count(&self, square: impl Into<Square>, by: Color) -> u8 {
+        self.attacker_counts[color_index(by)][square.into().to_index()]
+    }
+}
+
+impl Board {
+    /// Compute the squares attacked by both colors in one pass over the board.
+    pub fn compute_attack_map(&self) -> AttackMap {
+        let mut map = AttackMap {
+            attacked: [0; 2],
+            attacker_counts: [[0; 64]; 2],
+        };
+
+        for (from, piece) in self.iter_pieces() {
+            let color = color_index(piece.color());
+
+            for target in Square::all().filter(|target| self.attacks(from, *target)) {
+                let index = target.to_index();
+
+                map.attacked[color] |= 1 << index;
+                map.attacker_counts[color][index] += 1;
+            }
+        }
+
+        map
+    }
+
+    /// Number of legal moves of the given color to each square, indexed by
+    /// `[rank][file]`. A promotion counts once however many promotion pieces are possible.
+    pub fn mobility_map(&self, color: Color) -> [[u8; 8]; 8] {
+        let mut map = [[0; 8]; 8];
+
+        for mv in self.legal_moves_for(color) {
+            if mv.promotion.is_none() || mv.promotion == Some(PieceType::Queen) {
+                map[mv.to.y as usize][mv.to.x as usize] += 1;
+            }
+        }
+
+        map
+    }
+}
+
+fn color_index(color: Color) -> usize {
+    match color {
+        Color::White => 0,
+        Color::Black => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fen::FromFen;
+
+    #[test]
+    fn test_attack_map_matches_is_square_attacked() {
+        let mut board =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+
+        for seed in 0..20 {
+            let map = board.compute_attack_map();
+
+            for square in Square::all() {
+                for color in &[Color::White, Color::Black] {
+                    assert_eq!(
+                        board.is_square_attacked(square, *color),
+                        map.is_attacked(square, *color)
+                    );
+                    assert_eq!(
+                        map.is_attacked(square, *color),
+                        map.attacked_squares(*color) & (1 << square.to_index()) != 0
+                    );
+                }
+            }
+
+            if board.play_random_move(seed).is_none() {
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn test_attacker_count() {
+        let board = Board::from_fen("4k3/8/8/3p4/2P1P3/8/8/3RK3 w - - 0 1");
+        let map = board.compute_attack_map();
+
+        assert_eq!(3, map.attacker_count((3, 4), Color::White));
+        assert_eq!(0, map.attacker_count((3, 4), Color::Black));
+        assert_eq!(1, map.attacker_count((2, 3), Color::Black));
+    }
+
+    #[test]
+    fn test_mobility_map() {
+        let board =
+            Board::from_fen("r1bqkb1r/pppp1ppp/2n2n2/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4");
+        let map = board.mobility_map(Color::White);
+
+        // d4 can be reached by the d-pawn and the knight on f3
+        assert_eq!(2, map[3][3]);
+        assert!(map[4][3] > 0);
+
+        for (rank, file) in &[(0, 0), (0, 7), (7, 0), (7, 7)] {
+            assert_eq!(0, map[*rank][*file]);
+            assert!(map[3][3] > map[*rank][*file]);
+        }
+
+        let total: u32 = map.iter().flatten().map(|c| *c as u32).sum();
+        assert_eq!(board.all_legal_moves().len() as u32, total);
+    }
+}
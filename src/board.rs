@@ -1,13 +1,15 @@
 use crate::{
     color::Color,
-    piece::{Piece, PieceType},
-    rule::{MoveRule, MoveType, PieceMove},
-    square::Square,
+    game::GameResult,
+    piece::{Piece, PieceType, PROMOTION_PIECE_TYPES},
+    rule::{Move, MoveRule, MoveType, PieceMove},
+    square::{Square, BOARD_SIZE},
+    zobrist::splitmix64,
 };
 
 #[derive(Copy, Clone, Debug)]
 pub struct Board {
-    pieces: [[Option<Piece>; 8]; 8],
+    pieces: [[Option<Piece>; BOARD_SIZE as usize]; BOARD_SIZE as usize],
     active_color: Color,
 
     white_castling: Castling,
@@ -17,19 +19,28 @@ pub struct Board {
 
     half_moves: u32,
     full_moves: u32,
+
+    castling_changes: [Option<CastlingRightsChange>; 2],
+    last_move: Option<Move>,
+    promotion_policy: PromotionPolicy,
+    result: Option<GameResult>,
 }
 
 impl Board {
     /// Initialize a new empty chess board
     pub fn empty() -> Self {
         Self {
-            pieces: [[None; 8]; 8],
+            pieces: [[None; BOARD_SIZE as usize]; BOARD_SIZE as usize],
             active_color: Color::White,
             white_castling: Castling::both(),
             black_castling: Castling::both(),
             en_passant_target: None,
             half_moves: 0,
             full_moves: 1,
+            castling_changes: [None; 2],
+            last_move: None,
+            promotion_policy: PromotionPolicy::default(),
+            result: None,
         }
     }
 
@@ -37,6 +48,18 @@ impl Board {
         self.pieces[x as usize][y as usize] = Some(Piece::new(piece_type, color, (x, y)))
     }
 
+    /// Place the piece on the square, replacing any piece there.
+    pub fn place(&mut self, square: impl Into<Square>, piece: Piece) {
+        let Square { x, y } = square.into();
+        *self.piece_mut(x, y) = Some(piece);
+    }
+
+    /// Place a new, unmoved piece on the square, replacing any piece there.
+    pub fn place_new(&mut self, square: impl Into<Square>, piece_type: PieceType, color: Color) {
+        let square = square.into();
+        self.place(square, Piece::new(piece_type, color, square));
+    }
+
     pub fn piece(&self, x: u8, y: u8) -> &Option<Piece> {
         &self.pieces[x as usize][y as usize]
     }
@@ -45,6 +68,35 @@ impl Board {
         &mut self.pieces[x as usize][y as usize]
     }
 
+    pub fn piece_at(&self, square: impl Into<Square>) -> Option<Piece> {
+        let Square { x, y } = square.into();
+        *self.piece(x, y)
+    }
+
+    pub fn is_empty(&self, square: impl Into<Square>) -> bool {
+        self.piece_at(square).is_none()
+    }
+
+    pub fn is_occupied(&self, square: impl Into<Square>) -> bool {
+        self.piece_at(square).is_some()
+    }
+
+    /// Whether the square holds a piece of the given type and color.
+    pub fn is_occupied_by(
+        &self,
+        square: impl Into<Square>,
+        piece_type: PieceType,
+        color: Color,
+    ) -> bool {
+        self.piece_at(square)
+            .filter(|p| p.piece_type() == piece_type && p.color() == color)
+            .is_some()
+    }
+
+    pub fn occupant_color(&self, square: impl Into<Square>) -> Option<Color> {
+        self.piece_at(square).map(|p| p.color())
+    }
+
     pub fn active_color(&self) -> Color {
         self.active_color
     }
@@ -73,8 +125,132 @@ impl Board {
         self.en_passant_target
     }
 
-    pub fn en_passant_target_mut(&mut self) -> &mut Option<Square> {
-        &mut self.en_passant_target
+    /// Set the en passant target square. The target must be on the third or the sixth rank,
+    /// otherwise it is rejected and the current target is kept.
+    pub fn set_en_passant(&mut self, square: Option<Square>) -> Result<(), InvalidEnPassantTarget> {
+        match square {
+            Some(square) if square.y != 2 && square.y != 5 => Err(InvalidEnPassantTarget(square)),
+            _ => {
+                self.en_passant_target = square;
+                Ok(())
+            }
+        }
+    }
+
+    /// Copy of the board with the given castling rights for the color.
+    pub fn with_castling(mut self, color: Color, castling: Castling) -> Self {
+        match color {
+            Color::White => self.white_castling = castling,
+            Color::Black => self.black_castling = castling,
+        }
+
+        self
+    }
+
+    /// Copy of the board with the given en passant target. An invalid target is rejected
+    /// like in [`Board::set_en_passant`] and the current target is kept.
+    pub fn with_en_passant(mut self, square: Option<Square>) -> Self {
+        let _ = self.set_en_passant(square);
+        self
+    }
+
+    /// Copy of the board with the given side to move.
+    pub fn with_active_color(mut self, color: Color) -> Self {
+        self.active_color = color;
+        self
+    }
+
+    /// Pass the turn to the other side without moving a piece, as done by null-move pruning
+    /// in search. The en passant target is cleared. Undo with [`Board::unmake_null_move`].
+    pub fn make_null_move(&mut self) -> NullUndo {
+        let undo = NullUndo {
+            en_passant_target: self.en_passant_target,
+        };
+
+        self.en_passant_target = None;
+        self.active_color = self.active_color.opposite();
+
+        undo
+    }
+
+    /// Undo a null move made with [`Board::make_null_move`].
+    pub fn unmake_null_move(&mut self, undo: NullUndo) {
+        self.en_passant_target = undo.en_passant_target;
+        self.active_color = self.active_color.opposite();
+    }
+
+    /// Save the full state of the board to be put back later with [`Board::restore`].
+    pub fn snapshot(&self) -> BoardSnapshot {
+        BoardSnapshot(*self)
+    }
+
+    /// Put back the state saved in a snapshot, undoing every move played since.
+    pub fn restore(&mut self, snapshot: BoardSnapshot) {
+        *self = snapshot.0;
+    }
+
+    /// Whether both boards hold the same position: the same pieces on the same squares, the
+    /// same side to move, castling rights and en passant target. Move counters are ignored.
+    pub fn is_same_position_as(&self, other: &Board) -> bool {
+        let same_pieces = Square::all().all(|square| {
+            let piece = |board: &Board| board.piece_at(square).map(|p| (p.piece_type(), p.color()));
+
+            piece(self) == piece(other)
+        });
+
+        same_pieces
+            && self.active_color == other.active_color
+            && self.white_castling == other.white_castling
+            && self.black_castling == other.black_castling
+            && self.en_passant_target == other.en_passant_target
+    }
+
+    pub fn promotion_policy(&self) -> PromotionPolicy {
+        self.promotion_policy
+    }
+
+    /// Set how [`Board::play_move`] handles a pawn reaching the last rank.
+    pub fn set_promotion_policy(&mut self, policy: PromotionPolicy) {
+        self.promotion_policy = policy;
+    }
+
+    /// Result imposed on the game from outside the board, such as by resignation, draw
+    /// agreement or timeout, or `None` if none is set.
+    pub fn result(&self) -> Option<GameResult> {
+        self.result
+    }
+
+    /// Impose a result that can't be derived from the position, such as a resignation.
+    /// It takes precedence over the result decided on the board in
+    /// [`Board::result_string`]. Moves can still be played on the board.
+    pub fn set_result(&mut self, result: GameResult) {
+        self.result = Some(result);
+    }
+
+    /// Square of the pawn waiting to be promoted after [`Board::play_move`] returned
+    /// `PawnPromote`, or `None` if no promotion is pending.
+    pub fn pending_promotion(&self) -> Option<Square> {
+        self.last_move
+            .map(|mv| mv.to)
+            .filter(|to| to.is_back_rank())
+            .filter(|to| self.piece_at(*to).map(|p| p.piece_type()) == Some(PieceType::Pawn))
+    }
+
+    /// The pieces a pending promotion can choose from, or `None` if no promotion is pending.
+    /// A pawn can't stay a pawn or become a king.
+    pub fn promotion_choices(&self) -> Option<[PieceType; 4]> {
+        self.pending_promotion().map(|_| PROMOTION_PIECE_TYPES)
+    }
+
+    /// Castling rights lost by either side during the last played move.
+    pub fn castling_rights_changed(&self) -> Vec<CastlingRightsChange> {
+        self.castling_changes.iter().filter_map(|c| *c).collect()
+    }
+
+    /// The last move played on this board, or `None` if no move has been played since
+    /// the position was set up.
+    pub fn last_move(&self) -> Option<Move> {
+        self.last_move
     }
 
     pub fn half_moves(&self) -> u32 {
@@ -101,6 +277,38 @@ impl Board {
             .collect::<Vec<_>>()
     }
 
+    /// All pieces on the board with their squares, in a1..h8 order.
+    pub fn iter_pieces(&self) -> impl Iterator<Item = (Square, Piece)> + '_ {
+        Square::all().filter_map(move |square| self.piece_at(square).map(|piece| (square, piece)))
+    }
+
+    /// Squares of the file from the first rank to the eighth, with their pieces.
+    pub fn file(&self, x: u8) -> impl Iterator<Item = (Square, Option<Piece>)> + '_ {
+        (0..BOARD_SIZE).map(move |y| (Square { x, y }, *self.piece(x, y)))
+    }
+
+    /// Squares of the rank from the a-file to the h-file, with their pieces.
+    pub fn rank(&self, y: u8) -> impl Iterator<Item = (Square, Option<Piece>)> + '_ {
+        (0..BOARD_SIZE).map(move |x| (Square { x, y }, *self.piece(x, y)))
+    }
+
+    /// Pieces of the given color with their squares, in a1..h8 order.
+    pub fn pieces_of(&self, color: Color) -> impl Iterator<Item = (Square, Piece)> + '_ {
+        self.iter_pieces()
+            .filter(move |(_, piece)| piece.color() == color)
+    }
+
+    /// Squares of all pieces of the given type and color.
+    pub fn find_all(&self, piece_type: PieceType, color: Color) -> Vec<Square> {
+        Square::all()
+            .filter(|square| {
+                self.piece(square.x, square.y)
+                    .filter(|p| p.piece_type() == piece_type && p.color() == color)
+                    .is_some()
+            })
+            .collect()
+    }
+
     pub fn change_piece_type<S>(&mut self, square: S, new_piece_type: PieceType)
     where
         S: Into<Square>,
@@ -112,8 +320,575 @@ impl Board {
         }
     }
 
+    /// The color-flipped equivalent of the position: ranks are mirrored, piece colors and
+    /// castling rights are swapped and the other side is to move. Move counters are kept.
+    pub fn flipped(&self) -> Board {
+        let mut board = Board::empty();
+
+        for (square, piece) in self.iter_pieces() {
+            board.set_piece(
+                square.x,
+                BOARD_SIZE - 1 - square.y,
+                piece.piece_type(),
+                piece.color().opposite(),
+            );
+        }
+
+        board.active_color = self.active_color.opposite();
+        board.white_castling = self.black_castling;
+        board.black_castling = self.white_castling;
+        board.en_passant_target = self.en_passant_target.map(|target| Square {
+            x: target.x,
+            y: BOARD_SIZE - 1 - target.y,
+        });
+        board.half_moves = self.half_moves;
+        board.full_moves = self.full_moves;
+        board.promotion_policy = self.promotion_policy;
+        board.result = self.result.map(|result| match result {
+            GameResult::WhiteWins => GameResult::BlackWins,
+            GameResult::BlackWins => GameResult::WhiteWins,
+            result => result,
+        });
+
+        board
+    }
+
+    /// All legal moves for the side to move. Pawn moves to the last rank are listed once
+    /// for every promotion piece.
+    pub fn all_legal_moves(&self) -> Vec<Move> {
+        self.generate_moves(true)
+    }
+
+    /// Moves for the side to move that follow each piece's movement pattern but may leave
+    /// the own king in check.
+    ///
+    /// The moves stay on the board, never capture an own piece, sliding pieces never jump
+    /// over other pieces and pawn and castling moves follow their usual occupancy rules.
+    /// Whether the king is in check before or after the move is not tested, so castling
+    /// out of check is included. Pawn moves to the last rank are listed once for every
+    /// promotion piece.
+    pub fn quasi_legal_moves(&self) -> Vec<Move> {
+        self.generate_moves(false)
+    }
+
+    /// Legal moves for the side to move that were generated with the given move type, such
+    /// as [`MoveType::Castling`] or [`MoveType::EnPassant`].
+    pub fn legal_moves_of_type(&self, move_type: MoveType) -> Vec<Move> {
+        self.generate_typed_moves(true)
+            .into_iter()
+            .filter(|(_, t)| *t == move_type)
+            .map(|(mv, _)| mv)
+            .collect()
+    }
+
+    fn generate_moves(&self, check_king_safety: bool) -> Vec<Move> {
+        self.generate_typed_moves(check_king_safety)
+            .into_iter()
+            .map(|(mv, _)| mv)
+            .collect()
+    }
+
+    fn generate_typed_moves(&self, check_king_safety: bool) -> Vec<(Move, MoveType)> {
+        let mut moves = Vec::new();
+        let king_safety = KingSafety::of(self, self.active_color);
+
+        for from in Square::all() {
+            if let Some(piece) = self
+                .piece(from.x, from.y)
+                .filter(|p| p.color() == self.active_color)
+            {
+                let mut valid_moves = self.pseudo_legal_moves(&piece, &from, check_king_safety);
+
+                if check_king_safety {
+                    self.retain_king_safe(&piece, &from, &mut valid_moves, &king_safety);
+                }
+
+                for valid_move in valid_moves {
+                    let to = valid_move.target;
+                    let move_type = valid_move.move_type;
+
+                    if piece.piece_type() == PieceType::Pawn && to.is_back_rank() {
+                        moves.extend(PROMOTION_PIECE_TYPES.iter().map(|promotion| {
+                            (Move::with_promotion(from, to, *promotion), move_type)
+                        }));
+                    } else {
+                        moves.push((Move::new(from, to), move_type));
+                    }
+                }
+            }
+        }
+
+        moves
+    }
+
+    /// Number of legal captures for the side to move, counted the same way as
+    /// [`Board::all_legal_moves`] lists them, without collecting the moves.
+    pub fn legal_capture_count(&self) -> usize {
+        Square::all()
+            .filter_map(|from| {
+                self.piece(from.x, from.y)
+                    .filter(|p| p.color() == self.active_color)
+                    .map(|piece| (from, piece))
+            })
+            .map(|(from, piece)| {
+                self.valid_moves(&piece, &from, true)
+                    .iter()
+                    .filter(|valid_move| self.is_capture(&Move::new(from, valid_move.target)))
+                    .map(|valid_move| {
+                        if piece.piece_type() == PieceType::Pawn && valid_move.target.is_back_rank()
+                        {
+                            PROMOTION_PIECE_TYPES.len()
+                        } else {
+                            1
+                        }
+                    })
+                    .sum::<usize>()
+            })
+            .sum()
+    }
+
+    /// Legal moves for the given color as if it were that color's turn.
+    ///
+    /// When `color` is not the side to move, the en passant target is ignored since it can
+    /// only be captured by the side to move. Castling rights are used as they are.
+    pub fn legal_moves_for(&self, color: Color) -> Vec<Move> {
+        let mut board = *self;
+
+        if color != self.active_color {
+            board.active_color = color;
+            board.en_passant_target = None;
+        }
+
+        board.all_legal_moves()
+    }
+
+    /// Whether the piece on the square belongs to the side to move and has at least one legal
+    /// move. Stops at the first legal move found.
+    pub fn can_move(&self, square: impl Into<Square>) -> bool {
+        let square = square.into();
+
+        let piece = match self.piece_at(square) {
+            Some(piece) if piece.color() == self.active_color => piece,
+            _ => return false,
+        };
+
+        self.has_legal_move(&piece, &square, &KingSafety::of(self, piece.color()))
+    }
+
+    fn has_legal_move(&self, piece: &Piece, square: &Square, king_safety: &KingSafety) -> bool {
+        piece.move_rules().iter().any(|move_rule| {
+            let mut moves = self.valid_moves_for_rule(piece, square, move_rule, true);
+            self.retain_king_safe(piece, square, &mut moves, king_safety);
+
+            !moves.is_empty()
+        })
+    }
+
+    /// Legal moves of the piece on `from`. Pawn moves to the last rank are listed once for
+    /// every promotion piece. Empty if the square is empty or holds a piece of the side not
+    /// to move.
+    pub fn moves_from(&self, from: impl Into<Square>) -> Vec<Move> {
+        let from = from.into();
+
+        self.all_legal_moves()
+            .into_iter()
+            .filter(|mv| mv.from == from)
+            .collect()
+    }
+
+    /// Every square the piece on `square` could reach by its movement pattern on an empty
+    /// board, with whether moving there is legal and, if not, why. Pawn double moves are
+    /// only listed from the starting rank and castling only from the king's home square.
+    pub fn annotated_moves(
+        &self,
+        square: impl Into<Square>,
+    ) -> Vec<(Square, Result<(), MoveError>)> {
+        let from = square.into();
+
+        let piece = match self.piece_at(from) {
+            Some(piece) => piece,
+            None => return Vec::new(),
+        };
+
+        let home_y = match piece.color() {
+            Color::White => 0,
+            Color::Black => BOARD_SIZE - 1,
+        };
+
+        let mut candidates: Vec<(Square, MoveType)> = Vec::new();
+
+        for rule in piece.move_rules() {
+            let mut target = from;
+
+            while let Some(next) = target.offset(rule.x_offset, rule.y_offset) {
+                target = next;
+
+                let listed = match rule.move_type {
+                    MoveType::PawnDoubleMove => {
+                        from.y == if piece.color() == Color::White { 1 } else { 6 }
+                    }
+                    MoveType::Castling => from == Square { x: 4, y: home_y },
+                    _ => true,
+                };
+
+                if listed && !candidates.iter().any(|(t, _)| *t == target) {
+                    candidates.push((target, rule.move_type));
+                }
+
+                if rule.move_type != MoveType::LineOfSight {
+                    break;
+                }
+            }
+        }
+
+        let legal = self.moves_from(from);
+        let pseudo_legal = self.pseudo_legal_moves(&piece, &from, true);
+
+        candidates
+            .into_iter()
+            .map(|(target, move_type)| {
+                let is_pawn_push = matches!(
+                    move_type,
+                    MoveType::PawnSingleMove | MoveType::PawnDoubleMove
+                );
+
+                let result = if piece.color() != self.active_color {
+                    Err(MoveError::NotYourTurn)
+                } else if legal.iter().any(|mv| mv.to == target) {
+                    Ok(())
+                } else if pseudo_legal.iter().any(|mv| mv.target == target) {
+                    Err(MoveError::WouldLeaveKingInCheck)
+                } else if move_type != MoveType::Normal
+                    && move_type != MoveType::PawnCapture
+                    && !self.is_line_clear(from, target)
+                {
+                    Err(MoveError::Blocked)
+                } else if self.occupant_color(target) == Some(piece.color()) {
+                    Err(MoveError::OccupiedByOwnPiece)
+                } else if is_pawn_push && self.is_occupied(target) {
+                    Err(MoveError::Blocked)
+                } else {
+                    Err(MoveError::IllegalMove)
+                };
+
+                (target, result)
+            })
+            .collect()
+    }
+
+    /// Bitboard of the squares the piece on the square can legally move to, using the bit of
+    /// [`Square::to_index`].
+    pub fn legal_destinations_bitboard(&self, square: impl Into<Square>) -> u64 {
+        self.moves_from(square)
+            .iter()
+            .fold(0, |bitboard, mv| bitboard | 1 << mv.to.to_index())
+    }
+
+    /// Squares the piece on `from` can legally move to, each listed once together with
+    /// whether the move promotes a pawn, so that the promotion piece can be asked for later.
+    pub fn legal_targets(&self, from: impl Into<Square>) -> Vec<(Square, bool)> {
+        let mut targets = self
+            .moves_from(from)
+            .into_iter()
+            .map(|mv| (mv.to, mv.promotion.is_some()))
+            .collect::<Vec<_>>();
+
+        targets.dedup();
+        targets
+    }
+
+    /// Whether the position could arise in a game: each side has exactly one king and
+    /// [plausible material](Board::has_plausible_material), the side not to move is not in
+    /// check, no pawns stand on the first or last rank, castling rights match the king and
+    /// rook placement and an en passant target lies behind a pawn that could just have made
+    /// a double move.
+    pub fn is_legal_position(&self) -> bool {
+        let sides_valid = [Color::White, Color::Black].iter().all(|color| {
+            self.find_all(PieceType::King, *color).len() == 1 && self.has_plausible_material(*color)
+        });
+
+        if !sides_valid || self.is_king_threatened(self.active_color.opposite()) {
+            return false;
+        }
+
+        let pawn_on_last_rank = Square::all().filter(Square::is_back_rank).any(|square| {
+            self.piece_at(square)
+                .filter(|p| p.piece_type() == PieceType::Pawn)
+                .is_some()
+        });
+
+        if pawn_on_last_rank {
+            return false;
+        }
+
+        for (castling, color) in [
+            (self.white_castling, Color::White),
+            (self.black_castling, Color::Black),
+        ]
+        .iter()
+        {
+            if castling.kingside && self.castling_rook(*color, CastleSide::Kingside).is_none()
+                || castling.queenside && self.castling_rook(*color, CastleSide::Queenside).is_none()
+            {
+                return false;
+            }
+        }
+
+        match self.en_passant_target {
+            Some(target) => {
+                let (target_y, pawn_y, pawn_color) = match self.active_color {
+                    Color::White => (5, 4, Color::Black),
+                    Color::Black => (2, 3, Color::White),
+                };
+
+                target.y == target_y
+                    && self.piece(target.x, target.y).is_none()
+                    && self.is_occupied_by((target.x, pawn_y), PieceType::Pawn, pawn_color)
+            }
+            None => true,
+        }
+    }
+
+    /// Whether the side to move is in check.
+    pub fn is_in_check(&self) -> bool {
+        self.is_king_threatened(self.active_color)
+    }
+
+    /// Whether the last move played gave check to the side now to move.
+    pub fn last_move_was_check(&self) -> bool {
+        self.last_move.is_some() && self.is_in_check()
+    }
+
+    /// Whether the side to move is in check and has no legal moves.
+    pub fn is_checkmate(&self) -> bool {
+        self.is_in_check() && self.all_legal_moves().is_empty()
+    }
+
+    /// Whether the game can go on and if the side to move is in check. A result imposed with
+    /// [`Board::set_result`] ends the game regardless of the position.
+    ///
+    /// Stops at the first legal move found, so it's cheaper than generating every legal move.
+    pub fn status(&self) -> GameStatus {
+        if let Some(result) = self.result {
+            return GameStatus::Decided(result);
+        }
+
+        let king_safety = KingSafety::of(self, self.active_color);
+        let has_moves = self
+            .pieces_of(self.active_color)
+            .any(|(square, piece)| self.has_legal_move(&piece, &square, &king_safety));
+
+        match (king_safety.in_check, has_moves) {
+            (false, true) => GameStatus::Ongoing,
+            (true, true) => GameStatus::Check,
+            (true, false) => GameStatus::Checkmate,
+            (false, false) => GameStatus::Stalemate,
+        }
+    }
+
+    /// Whether the side to move is not in check but has no legal moves.
+    pub fn is_stalemate(&self) -> bool {
+        !self.is_in_check() && self.all_legal_moves().is_empty()
+    }
+
+    /// Legal moves that don't leave the opponent stalemated.
+    pub fn moves_avoiding_stalemate(&self) -> Vec<Move> {
+        self.all_legal_moves()
+            .into_iter()
+            .filter(|mv| {
+                let mut board = *self;
+                board.play(*mv);
+                !board.is_stalemate()
+            })
+            .collect()
+    }
+
+    /// Parse a legal move in coordinate notation. Besides bare UCI such as `e2e4` or `e7e8q`,
+    /// the squares may be separated by `-`, `x` or a space, as in `e2-e4`, `e4xd5` or `e2 e4`.
+    pub fn parse_coordinate_move(&self, s: &str) -> Option<Move> {
+        let s = s.trim();
+
+        if !s.is_ascii() || s.len() < 4 {
+            return None;
+        }
+
+        let uci = match s.as_bytes()[2] {
+            b'-' | b'x' | b'X' | b' ' => format!("{}{}", &s[..2], &s[3..]),
+            _ => s.to_string(),
+        };
+
+        Move::from_uci(&uci).filter(|mv| self.is_legal(mv))
+    }
+
+    /// Whether moving the piece on `from` to `to` is a legal pawn move to the last rank, which
+    /// needs a promotion piece to be chosen.
+    pub fn is_promotion(&self, from: impl Into<Square>, to: impl Into<Square>) -> bool {
+        let from = from.into();
+        let to = to.into();
+
+        self.is_occupied_by(from, PieceType::Pawn, self.active_color)
+            && to.is_back_rank()
+            && self.is_legal(&Move::with_promotion(from, to, PieceType::Queen))
+    }
+
+    /// The legal move of the piece on `from` to `to`, or `None` if there is none. A pawn
+    /// move to the last rank is returned with `promotion: None`, which marks that a promotion
+    /// piece still has to be chosen before the move can be played, see [`Board::is_promotion`].
+    pub fn resolve_move(&self, from: impl Into<Square>, to: impl Into<Square>) -> Option<Move> {
+        let mv = Move::new(from, to);
+
+        if self.is_legal(&mv) || self.is_promotion(mv.from, mv.to) {
+            Some(mv)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the move is legal for the side to move. A move that promotes a pawn must name
+    /// the promotion piece, and other moves must not.
+    pub fn is_legal(&self, mv: &Move) -> bool {
+        let piece = match self.piece(mv.from.x, mv.from.y) {
+            Some(piece) if piece.color() == self.active_color => *piece,
+            _ => return false,
+        };
+
+        let promotes = piece.piece_type() == PieceType::Pawn && mv.to.is_back_rank();
+
+        let valid_promotion = match mv.promotion {
+            Some(PieceType::Pawn) | Some(PieceType::King) => false,
+            Some(_) => promotes,
+            None => !promotes,
+        };
+
+        valid_promotion
+            && self
+                .valid_moves(&piece, &mv.from, true)
+                .iter()
+                .any(|valid_move| valid_move.target == mv.to)
+    }
+
+    /// Whether the move captures a piece, including en passant captures.
+    pub fn is_capture(&self, mv: &Move) -> bool {
+        self.captured_piece(mv).is_some()
+    }
+
+    fn captured_piece(&self, mv: &Move) -> Option<Piece> {
+        let piece = (*self.piece(mv.from.x, mv.from.y))?;
+
+        if let Some(target) = self
+            .piece(mv.to.x, mv.to.y)
+            .filter(|p| p.color() != piece.color())
+        {
+            return Some(target);
+        }
+
+        if piece.piece_type() == PieceType::Pawn
+            && mv.from.x != mv.to.x
+            && Some(mv.to) == self.en_passant_target
+        {
+            return *self.piece(mv.to.x, mv.from.y);
+        }
+
+        None
+    }
+
+    /// Sort moves for search using MVV-LVA (most valuable victim, least valuable attacker).
+    /// Captures come first, ordered by the value of the captured piece and then by the value
+    /// of the capturing piece. Quiet moves keep their relative order after the captures.
+    pub fn sort_moves_mvv_lva(&self, moves: &mut [Move]) {
+        moves.sort_by_key(|mv| match self.captured_piece(mv) {
+            Some(victim) => {
+                let attacker = self
+                    .piece(mv.from.x, mv.from.y)
+                    .map(|p| p.piece_type().value())
+                    .unwrap_or(0);
+
+                (0, u32::MAX - victim.piece_type().value(), attacker)
+            }
+            None => (1, 0, 0),
+        });
+    }
+
+    /// Play a legal move for the side to move, promoting the pawn if the move is a promotion.
+    /// Returns [`MoveResult::Invalid`] without changing the board if the move is not legal.
+    pub fn play(&mut self, mv: Move) -> MoveResult {
+        if !self.is_legal(&mv) {
+            return MoveResult::Invalid;
+        }
+
+        let result = self.play_move(mv.from, mv.to);
+
+        match mv.promotion {
+            Some(promotion) => {
+                self.change_piece_type(mv.to, promotion);
+                self.last_move = Some(mv);
+                MoveResult::Ok
+            }
+            None => result,
+        }
+    }
+
+    /// Play a move like [`Board::play_move`], promoting a pawn reaching the last rank to the
+    /// given piece type right away. A king or pawn is not a valid promotion and makes the
+    /// promotion `Invalid`. For other moves the piece type is ignored.
+    pub fn play_move_with_promotion(
+        &mut self,
+        from: impl Into<Square>,
+        to: impl Into<Square>,
+        promote_to: PieceType,
+    ) -> MoveResult {
+        let from = from.into();
+        let to = to.into();
+
+        if !self.is_promotion(from, to) {
+            return self.play_move(from, to);
+        }
+
+        match promote_to {
+            PieceType::King | PieceType::Pawn => MoveResult::Invalid,
+            _ => self.play(Move::with_promotion(from, to, promote_to)),
+        }
+    }
+
+    /// Castle to the given side. Invalid if it's not the color's turn or castling there is
+    /// not legal.
+    pub fn castle(&mut self, color: Color, side: CastleSide) -> MoveResult {
+        if color != self.active_color {
+            return MoveResult::Invalid;
+        }
+
+        let castling = self
+            .legal_moves_of_type(MoveType::Castling)
+            .into_iter()
+            .find(|mv| (mv.to.x > mv.from.x) == (side == CastleSide::Kingside));
+
+        match castling {
+            Some(mv) => self.play(mv),
+            None => MoveResult::Invalid,
+        }
+    }
+
+    /// Play a uniformly random legal move chosen with the given seed and return it,
+    /// or `None` if the side to move has no legal moves.
+    pub fn play_random_move(&mut self, rng_seed: u64) -> Option<Move> {
+        let moves = self.all_legal_moves();
+
+        if moves.is_empty() {
+            return None;
+        }
+
+        let (_, random) = splitmix64(rng_seed);
+        let mv = moves[(random % moves.len() as u64) as usize];
+        self.play(mv);
+
+        Some(mv)
+    }
+
     /// Play a move on the board.
     /// The result indicates whether the move was valid regular move or a pawn promotion.
+    /// A pawn reaching the last rank is promoted to a queen, unless the promotion policy is
+    /// [`PromotionPolicy::Prompt`], in which case the pawn is left for the caller to promote
+    /// with [`Board::change_piece_type`] and `PawnPromote` is returned.
     ///
     /// # Examples
     /// ```
@@ -132,7 +907,11 @@ impl Board {
                 .find(|valid_move| valid_move.target == to);
 
             if let Some(valid_move) = valid_move {
-                if piece.piece_type() == PieceType::Pawn || self.piece(to.x, to.y).is_some() {
+                // Checked before a promotion changes the piece type, so promotions reset
+                // the clock like any pawn move.
+                if piece.piece_type() == PieceType::Pawn
+                    || self.occupant_color(to) == Some(piece.color().opposite())
+                {
                     self.half_moves = 0;
                 } else {
                     self.half_moves += 1;
@@ -140,34 +919,35 @@ impl Board {
 
                 piece.increment_move_count();
 
-                if let Some(en_passant_target) = self.en_passant_target {
-                    if valid_move.move_type == MoveType::PawnCapture
-                        && valid_move.target == en_passant_target
-                    {
-                        let capture_y = if en_passant_target.y == 2 { 3 } else { 4 };
-                        *self.piece_mut(to.x, capture_y) = None;
-                    }
+                if valid_move.move_type == MoveType::EnPassant {
+                    *self.piece_mut(to.x, from.y) = None;
                 }
 
-                if valid_move.move_type == MoveType::Castling {
-                    let (rook_from_x, rook_to_x) = if to.x > from.x {
-                        (7, to.x - 1)
-                    } else {
-                        (0, to.x + 1)
-                    };
+                let castling_before = [self.white_castling, self.black_castling];
+                let castling_rooks = self.castling_rooks();
+                let castling_squares = self.castling_squares(from, to);
 
-                    let mut rook = self.piece(rook_from_x, to.y).unwrap();
-                    rook.increment_move_count();
+                *self.piece_mut(from.x, from.y) = None;
 
-                    *self.piece_mut(rook_from_x, to.y) = None;
-                    *self.piece_mut(rook_to_x, to.y) = Some(rook);
-                }
+                match castling_squares {
+                    Some((rook_from, king_to, rook_to))
+                        if valid_move.move_type == MoveType::Castling =>
+                    {
+                        let mut rook = self.piece_at(rook_from).unwrap();
+                        rook.increment_move_count();
 
-                *self.piece_mut(from.x, from.y) = None;
-                *self.piece_mut(to.x, to.y) = Some(piece);
+                        *self.piece_mut(rook_from.x, rook_from.y) = None;
+                        *self.piece_mut(rook_to.x, rook_to.y) = Some(rook);
+                        *self.piece_mut(king_to.x, king_to.y) = Some(piece);
+                    }
+                    _ => *self.piece_mut(to.x, to.y) = Some(piece),
+                }
 
                 self.update_en_passant(&valid_move);
-                self.update_castling_availability(&piece);
+                self.update_castling_availability(&piece, &castling_rooks, from, to);
+
+                self.record_castling_changes(castling_before, &piece);
+                self.last_move = Some(Move::new(from, to));
 
                 self.active_color = if self.active_color == Color::Black {
                     self.full_moves += 1;
@@ -176,10 +956,17 @@ impl Board {
                     Color::Black
                 };
 
-                return if (to.y == 0 || to.y == 7) && piece.piece_type() == PieceType::Pawn {
-                    MoveResult::PawnPromote
-                } else {
-                    MoveResult::Ok
+                if !to.is_back_rank() || piece.piece_type() != PieceType::Pawn {
+                    return MoveResult::Ok;
+                }
+
+                return match self.promotion_policy {
+                    PromotionPolicy::AutoQueen => {
+                        self.change_piece_type(to, PieceType::Queen);
+                        self.last_move = Some(Move::with_promotion(from, to, PieceType::Queen));
+                        MoveResult::Ok
+                    }
+                    PromotionPolicy::Prompt => MoveResult::PawnPromote,
                 };
             }
         }
@@ -201,23 +988,155 @@ impl Board {
         self.en_passant_target = en_passant_target;
     }
 
-    fn update_castling_availability(&mut self, moved_piece: &Piece) {
-        let castling = match moved_piece.color() {
-            Color::Black => &mut self.black_castling,
-            Color::White => &mut self.white_castling,
-        };
+    fn update_castling_availability(
+        &mut self,
+        moved_piece: &Piece,
+        castling_rooks: &[CastlingRooks; 2],
+        from: Square,
+        to: Square,
+    ) {
+        if moved_piece.piece_type() == PieceType::King {
+            match moved_piece.color() {
+                Color::Black => self.black_castling = Castling::none(),
+                Color::White => self.white_castling = Castling::none(),
+            }
+        }
 
-        match moved_piece.piece_type() {
-            PieceType::King => *castling = Castling::none(),
-            PieceType::Rook if moved_piece.initial_square().x == 0 => castling.kingside = false,
-            PieceType::Rook if moved_piece.initial_square().x == 7 => castling.queenside = false,
-            _ => {}
+        // A castling rook leaving its square, or being captured there, loses that side's
+        // castling.
+        for (castling, rooks) in [&mut self.white_castling, &mut self.black_castling]
+            .iter_mut()
+            .zip(castling_rooks.iter())
+        {
+            if rooks.kingside == Some(from) || rooks.kingside == Some(to) {
+                castling.kingside = false;
+            }
+            if rooks.queenside == Some(from) || rooks.queenside == Some(to) {
+                castling.queenside = false;
+            }
         }
     }
 
-    pub fn valid_moves(
-        &self,
-        piece: &Piece,
+    /// Squares of the rooks each color would castle with, see [`Board::castling_rook`].
+    fn castling_rooks(&self) -> [CastlingRooks; 2] {
+        let rooks = |color| CastlingRooks {
+            kingside: self.castling_rook(color, CastleSide::Kingside),
+            queenside: self.castling_rook(color, CastleSide::Queenside),
+        };
+
+        [rooks(Color::White), rooks(Color::Black)]
+    }
+
+    /// The rook the king of the given color castles with to the given side: the outermost
+    /// rook on that side of the king on the home rank, as in X-FEN. Castling rights are not
+    /// considered. This supports Chess960 starting positions, where the king and rooks may
+    /// start on any file.
+    pub fn castling_rook(&self, color: Color, side: CastleSide) -> Option<Square> {
+        let home_y = match color {
+            Color::White => 0,
+            Color::Black => BOARD_SIZE - 1,
+        };
+
+        let king_x =
+            (0..BOARD_SIZE).find(|x| self.is_occupied_by((*x, home_y), PieceType::King, color))?;
+
+        let is_rook = |x: &u8| self.is_occupied_by((*x, home_y), PieceType::Rook, color);
+
+        let rook_x = match side {
+            CastleSide::Kingside => (king_x + 1..BOARD_SIZE).rev().find(is_rook),
+            CastleSide::Queenside => (0..king_x).find(is_rook),
+        };
+
+        rook_x.map(|x| Square { x, y: home_y })
+    }
+
+    /// Rook square, king destination and rook destination of a castling move of the king on
+    /// `from` to `to`, or `None` if the move isn't shaped like castling.
+    ///
+    /// Standard castling moves the king two files from the e-file. In Chess960 positions
+    /// the king may already stand on its destination or next to the rook, so there castling
+    /// is written as the king moving to the square of its own rook.
+    fn castling_squares(&self, from: Square, to: Square) -> Option<(Square, Square, Square)> {
+        let king = self
+            .piece_at(from)
+            .filter(|p| p.piece_type() == PieceType::King && from.y == to.y)?;
+
+        let rook_from = if self.is_occupied_by(to, PieceType::Rook, king.color()) {
+            to
+        } else if from.x == 4 && (to.x == 2 || to.x == 6) {
+            Square {
+                x: if to.x > from.x { 7 } else { 0 },
+                y: to.y,
+            }
+        } else {
+            return None;
+        };
+
+        let (king_to_x, rook_to_x) = if rook_from.x > from.x { (6, 5) } else { (2, 3) };
+
+        Some((
+            rook_from,
+            Square {
+                x: king_to_x,
+                y: from.y,
+            },
+            Square {
+                x: rook_to_x,
+                y: from.y,
+            },
+        ))
+    }
+
+    fn record_castling_changes(&mut self, before: [Castling; 2], moved_piece: &Piece) {
+        let after = [self.white_castling, self.black_castling];
+
+        for (i, color) in [Color::White, Color::Black].iter().enumerate() {
+            let lost = Castling {
+                kingside: before[i].kingside && !after[i].kingside,
+                queenside: before[i].queenside && !after[i].queenside,
+            };
+
+            self.castling_changes[i] = if lost == Castling::none() {
+                None
+            } else {
+                let reason = if *color != moved_piece.color() {
+                    CastlingLossReason::RookCaptured
+                } else if moved_piece.piece_type() == PieceType::King {
+                    CastlingLossReason::KingMoved
+                } else {
+                    CastlingLossReason::RookMoved
+                };
+
+                Some(CastlingRightsChange {
+                    color: *color,
+                    lost,
+                    reason,
+                })
+            };
+        }
+    }
+
+    pub fn valid_moves(
+        &self,
+        piece: &Piece,
+        square: &Square,
+        check_king_safety: bool,
+    ) -> Vec<PieceMove> {
+        let mut valid_moves = self.pseudo_legal_moves(piece, square, check_king_safety);
+
+        if check_king_safety {
+            let king_safety = KingSafety::of(self, piece.color());
+            self.retain_king_safe(piece, square, &mut valid_moves, &king_safety);
+        }
+
+        valid_moves
+    }
+
+    /// Moves following the piece's move rules. With `check_king_safety` set, castling out of
+    /// or through check is excluded, but other moves may still leave the king in check.
+    fn pseudo_legal_moves(
+        &self,
+        piece: &Piece,
         square: &Square,
         check_king_safety: bool,
     ) -> Vec<PieceMove> {
@@ -235,6 +1154,28 @@ impl Board {
         valid_moves
     }
 
+    /// Remove the moves that would leave the piece's king in check.
+    fn retain_king_safe(
+        &self,
+        piece: &Piece,
+        square: &Square,
+        valid_moves: &mut Vec<PieceMove>,
+        king_safety: &KingSafety,
+    ) {
+        // Moving a piece that isn't pinned can't expose its king, so the position only
+        // needs to be simulated for king moves, en passant, pinned pieces and when in check.
+        let needs_simulation = piece.piece_type() == PieceType::King
+            || king_safety.in_check
+            || king_safety.pinned.contains(square);
+
+        valid_moves.retain(|valid_move| {
+            !(needs_simulation || valid_move.move_type == MoveType::EnPassant)
+                || !self
+                    .board_after(piece, *square, valid_move.target)
+                    .is_king_threatened(piece.color())
+        });
+    }
+
     fn valid_moves_for_rule(
         &self,
         piece: &Piece,
@@ -244,15 +1185,22 @@ impl Board {
     ) -> Vec<PieceMove> {
         let mut valid_moves = Vec::new();
 
-        let target_square = Square::from((
-            (square.x as i8 + move_rule.x_offset) as _,
-            (square.y as i8 + move_rule.y_offset) as _,
-        ));
+        if move_rule.move_type == MoveType::Castling {
+            let side = if move_rule.x_offset > 0 {
+                CastleSide::Kingside
+            } else {
+                CastleSide::Queenside
+            };
 
-        if target_square.x > 7 || target_square.y > 7 {
+            valid_moves.extend(self.castling_move(piece, *square, side, check_king_safety));
             return valid_moves;
         }
 
+        let target_square = match square.offset(move_rule.x_offset, move_rule.y_offset) {
+            Some(target_square) => target_square,
+            None => return valid_moves,
+        };
+
         let target = *self.piece(target_square.x, target_square.y);
         let move_type = move_rule.move_type;
 
@@ -297,8 +1245,14 @@ impl Board {
                 }
             }
             MoveType::PawnDoubleMove => {
-                if !piece.has_moved() && target.is_none() {
-                    // Target square must be empty and must be first move
+                let start_rank = if move_rule.y_offset > 0 { 1 } else { 6 };
+                let skipped_y = (square.y as i8 + move_rule.y_offset / 2) as u8;
+
+                if square.y == start_rank
+                    && target.is_none()
+                    && self.piece(square.x, skipped_y).is_none()
+                {
+                    // Pawn must be on its starting rank and both squares ahead must be empty
 
                     valid_moves.push(PieceMove {
                         move_type,
@@ -307,96 +1261,337 @@ impl Board {
                 }
             }
             MoveType::PawnCapture => {
-                if target.filter(|p| p.color() != piece.color()).is_some()
-                    || Some(target_square).eq(&self.en_passant_target)
-                {
+                if target.filter(|p| p.color() != piece.color()).is_some() {
                     // Target square must have different color piece
-                    // or it must be an active en passant target.
 
                     valid_moves.push(PieceMove {
                         move_type,
                         target: target_square,
                     });
+                } else if target.is_none() && Some(target_square) == self.en_passant_target {
+                    // or it must be an active en passant target.
+
+                    valid_moves.push(PieceMove {
+                        move_type: MoveType::EnPassant,
+                        target: target_square,
+                    });
                 }
             }
+            MoveType::EnPassant => {
+                // En passant captures are generated by the pawn capture rules
+            }
             MoveType::Castling => {
-                if !piece.has_moved() && target.is_none() {
-                    let (rook_x, dir_x) = if move_rule.x_offset < 0 {
-                        (0, -1)
-                    } else {
-                        (7, 1)
-                    };
-
-                    let rook_has_not_moved = self
-                        .piece(rook_x, target_square.y)
-                        .filter(|p| p.piece_type() == PieceType::Rook && !p.has_moved())
-                        .is_some();
-
-                    if rook_has_not_moved && self
-                            .valid_moves_for_rule(
-                                piece,
-                                square,
-                                &MoveRule::line_of_sight(dir_x, 0),
-                                check_king_safety,
-                            )
-                            .len()
-                            == (square.x as i8 - rook_x as i8).abs() as usize - 1 && !self.is_king_threatened(piece.color()) {
-                        valid_moves.push(PieceMove {
-                            move_type,
-                            target: target_square,
-                        })
-                    }
-                }
+                // Castling moves are generated before the target square is computed
             }
         }
 
-        if check_king_safety {
-            valid_moves.retain(|valid_move| {
-                let mut board_copy = *self;
-                *board_copy.piece_mut(valid_move.target.x, valid_move.target.y) = Some(*piece);
-                *board_copy.piece_mut(square.x, square.y) = None;
+        valid_moves
+    }
+
+    /// Castling of the king on `square` to the given side, if the right is available, the
+    /// squares between the king, the rook and their destinations are empty and, with
+    /// `check_king_safety` set, the king doesn't castle out of or through check. Whether the
+    /// destination is attacked is tested together with the other moves.
+    fn castling_move(
+        &self,
+        king: &Piece,
+        square: Square,
+        side: CastleSide,
+        check_king_safety: bool,
+    ) -> Option<PieceMove> {
+        let castling = match king.color() {
+            Color::Black => self.black_castling,
+            Color::White => self.white_castling,
+        };
+
+        let available = match side {
+            CastleSide::Kingside => castling.kingside,
+            CastleSide::Queenside => castling.queenside,
+        };
 
-                !board_copy.is_king_threatened(piece.color())
-            });
+        let rook_from = self.castling_rook(king.color(), side)?;
+
+        if !available || rook_from.y != square.y {
+            return None;
         }
 
-        valid_moves
+        let standard = square.x == 4 && (rook_from.x == 0 || rook_from.x == 7);
+        let target = if standard {
+            Square {
+                x: if side == CastleSide::Kingside { 6 } else { 2 },
+                y: square.y,
+            }
+        } else {
+            rook_from
+        };
+
+        let (_, king_to, rook_to) = self.castling_squares(square, target)?;
+
+        let xs = [square.x, rook_from.x, king_to.x, rook_to.x];
+        let path_is_empty = (*xs.iter().min().unwrap()..=*xs.iter().max().unwrap())
+            .filter(|x| *x != square.x && *x != rook_from.x)
+            .all(|x| self.piece(x, square.y).is_none());
+
+        let passes_safely = !check_king_safety
+            || !self.is_king_threatened(king.color())
+                && (square.x.min(king_to.x) + 1..square.x.max(king_to.x))
+                    .all(|x| !self.is_square_attacked((x, square.y), king.color().opposite()));
+
+        if path_is_empty && passes_safely {
+            Some(PieceMove {
+                move_type: MoveType::Castling,
+                target,
+            })
+        } else {
+            None
+        }
     }
 
-    fn is_king_threatened(&self, color: Color) -> bool {
-        let (_, king_square) = self.find_piece(PieceType::King, color).unwrap();
-
-        for x in 0..8 {
-            for y in 0..8 {
-                if let Some(piece) = self.piece(x, y).filter(|p| p.color() != color).as_ref() {
-                    for piece_move in self.valid_moves(piece, &Square::from((x, y)), false) {
-                        if piece_move.target == king_square {
-                            return true;
-                        }
+    /// The en passant target, but only if a pawn of the side to move can legally capture
+    /// en passant. FEN writers that only print capturable targets use this form.
+    pub fn en_passant_legal_target(&self) -> Option<Square> {
+        self.en_passant_target
+            .filter(|_| !self.en_passant_capturers().is_empty())
+    }
+
+    /// Squares of the pawns of the side to move that can legally capture en passant.
+    ///
+    /// A capture is rejected when removing both pawns from the rank would expose the
+    /// capturing side's king.
+    pub fn en_passant_capturers(&self) -> Vec<Square> {
+        let target = match self.en_passant_target {
+            Some(target) => target,
+            None => return Vec::new(),
+        };
+
+        let pawn_dy = if target.y == 2 { 1 } else { -1 };
+
+        [-1, 1]
+            .iter()
+            .filter_map(|dx| target.offset(*dx, pawn_dy))
+            .filter(|from| {
+                let pawn = match self.piece(from.x, from.y) {
+                    Some(p)
+                        if p.piece_type() == PieceType::Pawn && p.color() == self.active_color =>
+                    {
+                        *p
                     }
-                }
+                    _ => return false,
+                };
+
+                self.valid_moves(&pawn, from, false)
+                    .iter()
+                    .any(|valid_move| valid_move.target == target)
+                    && !self
+                        .board_after(&pawn, *from, target)
+                        .is_king_threatened(pawn.color())
+            })
+            .collect()
+    }
+
+    /// Copy of the board with the piece moved from one square to another, including the
+    /// removal of a pawn captured en passant. Nothing else about the position is updated.
+    fn board_after(&self, piece: &Piece, from: Square, to: Square) -> Board {
+        let mut board_copy = *self;
+
+        if piece.piece_type() == PieceType::Pawn
+            && from.x != to.x
+            && Some(to) == self.en_passant_target
+            && self.piece(to.x, to.y).is_none()
+        {
+            *board_copy.piece_mut(to.x, from.y) = None;
+        }
+
+        match self.castling_squares(from, to) {
+            Some((rook_from, king_to, rook_to)) => {
+                let rook = board_copy.piece_at(rook_from);
+
+                *board_copy.piece_mut(from.x, from.y) = None;
+                *board_copy.piece_mut(rook_from.x, rook_from.y) = None;
+                *board_copy.piece_mut(rook_to.x, rook_to.y) = rook;
+                *board_copy.piece_mut(king_to.x, king_to.y) = Some(*piece);
+            }
+            None => {
+                *board_copy.piece_mut(to.x, to.y) = Some(*piece);
+                *board_copy.piece_mut(from.x, from.y) = None;
             }
         }
 
-        false
+        board_copy
     }
 
-    fn find_piece(&self, piece_type: PieceType, piece_color: Color) -> Option<(Piece, Square)> {
-        for x in 0..8 {
-            for y in 0..8 {
-                if let Some(piece) = self
-                    .piece(x, y)
-                    .filter(|p| p.piece_type() == piece_type && p.color() == piece_color)
-                {
-                    return Some((piece, Square::from((x, y))));
-                }
+    fn is_king_threatened(&self, color: Color) -> bool {
+        match self.find_piece(PieceType::King, color) {
+            Some((_, king_square)) => self.is_square_attacked(king_square, color.opposite()),
+            None => false,
+        }
+    }
+
+    /// Squares of the pieces of the given color that attack the square.
+    pub fn attackers_of(&self, square: impl Into<Square>, by: Color) -> Vec<Square> {
+        let target = square.into();
+
+        self.iter_pieces()
+            .filter(|(from, piece)| piece.color() == by && self.attacks(*from, target))
+            .map(|(from, _)| from)
+            .collect()
+    }
+
+    /// Squares of the pieces of the given color and type that attack the square.
+    pub fn attackers_of_type(
+        &self,
+        square: impl Into<Square>,
+        by: Color,
+        piece_type: PieceType,
+    ) -> Vec<Square> {
+        let target = square.into();
+
+        self.attackers_of(target, by)
+            .into_iter()
+            .filter(|from| self.piece_at(*from).map(|p| p.piece_type()) == Some(piece_type))
+            .collect()
+    }
+
+    /// Whether any piece of the given color attacks the square.
+    pub fn is_square_attacked(&self, square: impl Into<Square>, by: Color) -> bool {
+        let target = square.into();
+
+        Square::all().any(|from| {
+            self.piece(from.x, from.y)
+                .filter(|p| p.color() == by)
+                .is_some()
+                && self.attacks(from, target)
+        })
+    }
+
+    /// Whether the piece on `from` attacks `target`, whether or not `target` is occupied.
+    /// Pawns attack diagonally forward and never attack the square they could move to.
+    pub fn attacks(&self, from: Square, target: Square) -> bool {
+        let piece = match self.piece(from.x, from.y) {
+            Some(piece) => piece,
+            None => return false,
+        };
+
+        let dx = target.x as i8 - from.x as i8;
+        let dy = target.y as i8 - from.y as i8;
+
+        match piece.piece_type() {
+            PieceType::Pawn => {
+                let forward = if piece.color() == Color::White { 1 } else { -1 };
+                dy == forward && dx.abs() == 1
+            }
+            PieceType::Knight => {
+                (dx.abs() == 1 && dy.abs() == 2) || (dx.abs() == 2 && dy.abs() == 1)
+            }
+            PieceType::King => dx.abs().max(dy.abs()) == 1,
+            PieceType::Rook => (dx == 0) != (dy == 0) && self.is_line_clear(from, target),
+            PieceType::Bishop => {
+                dx != 0 && dx.abs() == dy.abs() && self.is_line_clear(from, target)
+            }
+            PieceType::Queen => {
+                ((dx == 0) != (dy == 0) || (dx != 0 && dx.abs() == dy.abs()))
+                    && self.is_line_clear(from, target)
             }
         }
-        None
     }
+
+    /// Whether all squares strictly between two squares on a rank, file or diagonal are empty.
+    fn is_line_clear(&self, from: Square, to: Square) -> bool {
+        let dx = (to.x as i8 - from.x as i8).signum();
+        let dy = (to.y as i8 - from.y as i8).signum();
+
+        let mut square = from;
+
+        loop {
+            square = match square.offset(dx, dy) {
+                Some(square) => square,
+                None => return false,
+            };
+
+            if square == to {
+                return true;
+            }
+
+            if self.piece(square.x, square.y).is_some() {
+                return false;
+            }
+        }
+    }
+
+    fn find_piece(&self, piece_type: PieceType, piece_color: Color) -> Option<(Piece, Square)> {
+        Square::all().find_map(|square| {
+            self.piece(square.x, square.y)
+                .filter(|p| p.piece_type() == piece_type && p.color() == piece_color)
+                .map(|piece| (piece, square))
+        })
+    }
+}
+
+/// State taken away by [`Board::make_null_move`], needed to undo it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct NullUndo {
+    en_passant_target: Option<Square>,
 }
 
+/// Saved state of a board, see [`Board::snapshot`].
 #[derive(Copy, Clone, Debug)]
+pub struct BoardSnapshot(Board);
+
+/// Reason a move was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveError {
+    /// There is no piece on the origin square.
+    NoPiece,
+    /// The piece on the origin square belongs to the side not to move.
+    NotYourTurn,
+    /// The piece can't legally move to the target square.
+    IllegalMove,
+    /// The move promotes a pawn but no promotion piece was given.
+    PromotionRequired,
+    /// Another piece stands in the way.
+    Blocked,
+    /// The target square holds a piece of the moving side.
+    OccupiedByOwnPiece,
+    /// The move would leave the moving side's king in check.
+    WouldLeaveKingInCheck,
+}
+
+impl std::fmt::Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveError::NoPiece => write!(f, "no piece on the origin square"),
+            MoveError::NotYourTurn => write!(f, "the piece belongs to the side not to move"),
+            MoveError::IllegalMove => write!(f, "the move is not legal"),
+            MoveError::PromotionRequired => write!(f, "the move requires a promotion piece"),
+            MoveError::Blocked => write!(f, "another piece is in the way"),
+            MoveError::OccupiedByOwnPiece => write!(f, "the target square holds an own piece"),
+            MoveError::WouldLeaveKingInCheck => write!(f, "the move would leave the king in check"),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+/// Error returned when setting an en passant target that is not on the third or sixth rank.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InvalidEnPassantTarget(pub Square);
+
+/// Check and pin state of one side's king, computed once per position for move generation.
+struct KingSafety {
+    in_check: bool,
+    pinned: Vec<Square>,
+}
+
+impl KingSafety {
+    fn of(board: &Board, color: Color) -> Self {
+        Self {
+            in_check: board.is_king_threatened(color),
+            pinned: board.pinned_pieces(color),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Castling {
     pub kingside: bool,
     pub queenside: bool,
@@ -417,9 +1612,1181 @@ impl Castling {
     }
 }
 
+/// Side of the board to castle to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CastleSide {
+    Kingside,
+    Queenside,
+}
+
+/// Squares of the rooks a color would castle with on either side.
+#[derive(Copy, Clone, Debug)]
+struct CastlingRooks {
+    kingside: Option<Square>,
+    queenside: Option<Square>,
+}
+
+/// Castling rights a side lost by a single move, and what caused the loss.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CastlingRightsChange {
+    pub color: Color,
+    pub lost: Castling,
+    pub reason: CastlingLossReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastlingLossReason {
+    KingMoved,
+    RookMoved,
+    RookCaptured,
+}
+
+/// State of the game for the side to move, see [`Board::status`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameStatus {
+    Ongoing,
+    Check,
+    Checkmate,
+    Stalemate,
+    /// The game ended with a result set by [`Board::set_result`], such as a resignation.
+    Decided(GameResult),
+}
+
+/// How [`Board::play_move`] handles a pawn reaching the last rank.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PromotionPolicy {
+    /// Promote the pawn to a queen.
+    #[default]
+    AutoQueen,
+    /// Leave the pawn on the last rank and return `MoveResult::PawnPromote`.
+    Prompt,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum MoveResult {
     Ok,
     PawnPromote,
     Invalid,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::*;
+    use crate::fen::{FromFen, IntoFen};
+
+    fn square(s: &str) -> Square {
+        Square::try_from(s).unwrap()
+    }
+
+    impl Board {
+        /// The first legal move that leaves the mover's king in check, or `None` if move
+        /// generation is sound in this position.
+        fn any_move_leaves_king_in_check(&self) -> Option<Move> {
+            self.all_legal_moves().into_iter().find(|mv| {
+                let mut board = *self;
+                board.play(*mv);
+                board.is_king_threatened(self.active_color)
+            })
+        }
+    }
+
+    #[test]
+    fn test_no_move_leaves_king_in_check() {
+        for fen in &[
+            // En passant capture exposing the king along the rank
+            "4k3/8/8/KPp4r/8/8/8/8 w - c6 0 2",
+            "8/8/8/8/k2Pp2Q/8/8/3K4 b - d3 0 1",
+            // Castling through or out of check
+            "4k3/8/8/8/8/8/8/R3K2r w Q - 0 1",
+            "r3k2r/8/8/8/4R3/8/8/4K3 b kq - 0 1",
+            "1r4kr/8/8/8/8/8/8/1R1r2KR w K - 0 1",
+            // Pinned pieces and double check
+            "4k3/4r3/8/8/1b6/8/3N4/4K3 w - - 0 1",
+            "4k3/8/8/8/1b6/8/3N4/r3K3 w - - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        ] {
+            let mut board = Board::from_fen(fen);
+
+            for seed in 0..40 {
+                assert_eq!(None, board.any_move_leaves_king_in_check(), "{}", fen);
+
+                if board.play_random_move(seed).is_none() {
+                    break;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_place() {
+        let mut board = Board::empty();
+
+        board.place_new(square("e4"), PieceType::Knight, Color::Black);
+        assert!(board.is_occupied_by(square("e4"), PieceType::Knight, Color::Black));
+        assert_eq!(
+            square("e4"),
+            board.piece_at(square("e4")).unwrap().initial_square()
+        );
+
+        board.place((0, 0), Piece::new(PieceType::Rook, Color::White, (7, 0)));
+        assert!(board.is_occupied_by(square("a1"), PieceType::Rook, Color::White));
+        assert_eq!(
+            square("h1"),
+            board.piece_at(square("a1")).unwrap().initial_square()
+        );
+    }
+
+    #[test]
+    fn test_file_and_rank() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+        let e_file = board
+            .file(4)
+            .map(|(square, piece)| (square, piece.map(|p| (p.piece_type(), p.color()))))
+            .collect::<Vec<_>>();
+
+        assert_eq!(8, e_file.len());
+        assert_eq!(
+            (square("e2"), Some((PieceType::Pawn, Color::White))),
+            e_file[1]
+        );
+        assert_eq!(
+            (square("e7"), Some((PieceType::Pawn, Color::Black))),
+            e_file[6]
+        );
+        assert_eq!((square("e4"), None), e_file[3]);
+
+        let squares = board.rank(2).map(|(square, _)| square).collect::<Vec<_>>();
+        assert_eq!(square("a3"), squares[0]);
+        assert_eq!(square("h3"), squares[7]);
+        assert!(board.rank(2).all(|(_, piece)| piece.is_none()));
+    }
+
+    #[test]
+    fn test_occupancy() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+        assert!(board.is_empty(square("e4")));
+        assert!(!board.is_occupied(square("e4")));
+        assert_eq!(None, board.occupant_color(square("e4")));
+
+        assert!(board.is_occupied(square("e2")));
+        assert!(!board.is_empty(square("e2")));
+        assert_eq!(Some(Color::White), board.occupant_color(square("e2")));
+        assert_eq!(Some(Color::Black), board.occupant_color((3, 7)));
+        assert_eq!(
+            Some(PieceType::Queen),
+            board.piece_at([3, 7]).map(|p| p.piece_type())
+        );
+
+        assert!(board.is_occupied_by(square("e1"), PieceType::King, Color::White));
+        assert!(!board.is_occupied_by(square("e1"), PieceType::King, Color::Black));
+        assert!(!board.is_occupied_by(square("e1"), PieceType::Queen, Color::White));
+        assert!(!board.is_occupied_by(square("e4"), PieceType::King, Color::White));
+    }
+
+    #[test]
+    fn test_is_legal_position() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert!(board.is_legal_position());
+
+        let board =
+            Board::from_fen("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2");
+        assert!(board.is_legal_position());
+
+        // Black is in check while it is White's turn
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4R1K1 w - - 0 1");
+        assert!(!board.is_legal_position());
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/8 w - - 0 1");
+        assert!(!board.is_legal_position());
+
+        let board = Board::from_fen("P3k3/8/8/8/8/8/8/4K3 w - - 0 1");
+        assert!(!board.is_legal_position());
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w K - 0 1");
+        assert!(!board.is_legal_position());
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - e6 0 1");
+        assert!(!board.is_legal_position());
+    }
+
+    #[test]
+    fn test_set_en_passant() {
+        let mut board =
+            Board::from_fen("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2");
+
+        assert_eq!(
+            Err(InvalidEnPassantTarget(square("e4"))),
+            board.set_en_passant(Some(square("e4")))
+        );
+        assert_eq!(Some(square("e6")), board.en_passant_target());
+
+        assert_eq!(Ok(()), board.set_en_passant(None));
+        assert_eq!(None, board.en_passant_target());
+
+        assert_eq!(Ok(()), board.set_en_passant(Some(square("d3"))));
+        assert_eq!(Some(square("d3")), board.en_passant_target());
+    }
+
+    #[test]
+    fn test_pieces_of() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+        assert_eq!(32, board.iter_pieces().count());
+        assert_eq!(16, board.pieces_of(Color::White).count());
+        assert!(board
+            .pieces_of(Color::Black)
+            .all(|(square, piece)| piece.color() == Color::Black && square.y >= 6));
+        assert_eq!(
+            Some((square("a1"), PieceType::Rook)),
+            board
+                .pieces_of(Color::White)
+                .next()
+                .map(|(square, piece)| (square, piece.piece_type()))
+        );
+    }
+
+    #[test]
+    fn test_sort_moves_mvv_lva() {
+        let board = Board::from_fen("4k3/8/8/3q4/4P3/8/2p5/1Q2K3 w - - 0 1");
+
+        let queen_takes_pawn = Move::new(square("b1"), square("c2"));
+        let pawn_takes_queen = Move::new(square("e4"), square("d5"));
+        let quiet = Move::new(square("e1"), square("f1"));
+
+        let mut moves = [quiet, queen_takes_pawn, pawn_takes_queen];
+        board.sort_moves_mvv_lva(&mut moves);
+
+        assert_eq!([pawn_takes_queen, queen_takes_pawn, quiet], moves);
+    }
+
+    #[test]
+    fn test_sort_moves_mvv_lva_prefers_cheaper_attacker() {
+        let board = Board::from_fen("4k3/8/8/3r4/4P3/8/8/3QK3 w - - 0 1");
+
+        let queen_takes_rook = Move::new(square("d1"), square("d5"));
+        let pawn_takes_rook = Move::new(square("e4"), square("d5"));
+
+        let mut moves = [queen_takes_rook, pawn_takes_rook];
+        board.sort_moves_mvv_lva(&mut moves);
+
+        assert_eq!([pawn_takes_rook, queen_takes_rook], moves);
+    }
+
+    #[test]
+    fn test_legal_moves_for() {
+        let board =
+            Board::from_fen("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2");
+
+        assert_eq!(board.all_legal_moves(), board.legal_moves_for(Color::White));
+        assert_eq!(29, board.legal_moves_for(Color::White).len());
+        assert_eq!(29, board.legal_moves_for(Color::Black).len());
+        assert!(board.legal_moves_for(Color::Black).iter().all(|mv| board
+            .piece(mv.from.x, mv.from.y)
+            .unwrap()
+            .color()
+            == Color::Black));
+    }
+
+    #[test]
+    fn test_en_passant_move_type() {
+        let board = Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1");
+        let pawn = board.piece_at(square("e5")).unwrap();
+
+        let move_types = board
+            .valid_moves(&pawn, &square("e5"), true)
+            .into_iter()
+            .map(|mv| (mv.target, mv.move_type))
+            .collect::<Vec<_>>();
+
+        assert!(move_types.contains(&(square("d6"), MoveType::EnPassant)));
+        assert!(move_types.contains(&(square("e6"), MoveType::PawnSingleMove)));
+        assert!(!move_types.iter().any(|(_, t)| *t == MoveType::PawnCapture));
+
+        let board = Board::from_fen("4k3/8/3n4/4P3/8/8/8/4K3 w - - 0 1");
+        let pawn = board.piece_at(square("e5")).unwrap();
+
+        assert!(board
+            .valid_moves(&pawn, &square("e5"), true)
+            .iter()
+            .any(|mv| mv.target == square("d6") && mv.move_type == MoveType::PawnCapture));
+    }
+
+    #[test]
+    fn test_en_passant_capturers() {
+        let board = Board::from_fen("4k3/8/8/KPp5/8/8/8/8 w - c6 0 2");
+        assert_eq!(vec![square("b5")], board.en_passant_capturers());
+
+        let board = Board::from_fen("4k3/8/8/1PpP4/8/8/8/4K3 w - c6 0 2");
+        assert_eq!(
+            vec![square("b5"), square("d5")],
+            board.en_passant_capturers()
+        );
+
+        let board = Board::from_fen("4k3/8/8/1Pp5/8/8/8/4K3 w - - 0 2");
+        assert!(board.en_passant_capturers().is_empty());
+    }
+
+    #[test]
+    fn test_en_passant_capturers_discovered_check() {
+        // Capturing would remove both pawns from the fifth rank and expose the king to the rook
+        let board = Board::from_fen("4k3/8/8/KPp4r/8/8/8/8 w - c6 0 2");
+
+        assert!(board.en_passant_capturers().is_empty());
+    }
+
+    #[test]
+    fn test_en_passant_pin_rejected() {
+        let mut board = Board::from_fen("4k3/8/8/KPp4r/8/8/8/8 w - c6 0 2");
+
+        assert!(!board
+            .all_legal_moves()
+            .contains(&Move::new(square("b5"), square("c6"))));
+        assert_eq!(
+            MoveResult::Invalid,
+            board.play_move(square("b5"), square("c6"))
+        );
+
+        let mut board = Board::from_fen("8/8/8/8/k2Pp2Q/8/8/3K4 b - d3 0 1");
+
+        assert_eq!(
+            MoveResult::Invalid,
+            board.play_move(square("e4"), square("d3"))
+        );
+    }
+
+    #[test]
+    fn test_quasi_legal_moves() {
+        let board = Board::from_fen("4k3/8/8/8/1b6/8/3N4/4K3 w - - 0 1");
+
+        let knight_move = Move::new(square("d2"), square("f3"));
+
+        assert!(board.quasi_legal_moves().contains(&knight_move));
+        assert!(!board.all_legal_moves().contains(&knight_move));
+        assert!(board
+            .all_legal_moves()
+            .iter()
+            .all(|mv| board.quasi_legal_moves().contains(mv)));
+        assert!(!board
+            .quasi_legal_moves()
+            .iter()
+            .any(|mv| board.occupant_color(mv.to) == Some(Color::White)));
+    }
+
+    #[test]
+    fn test_full_moves_after_loaded_black_move() {
+        let mut board = Board::from_fen("4k3/4p3/8/8/8/8/8/4K3 b - - 3 10");
+
+        assert_eq!(MoveResult::Ok, board.play_move(square("e7"), square("e6")));
+        assert_eq!(11, board.full_moves());
+        assert_eq!(Color::White, board.active_color());
+
+        assert_eq!(MoveResult::Ok, board.play_move(square("e1"), square("d1")));
+        assert_eq!(11, board.full_moves());
+        assert_eq!(Color::Black, board.active_color());
+    }
+
+    #[test]
+    fn test_en_passant_target_after_double_move() {
+        let mut board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+        board.play_move(square("e2"), square("e4"));
+        assert_eq!(Some(square("e3")), board.en_passant_target());
+        assert_eq!(
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+            board.into_fen()
+        );
+
+        board.play_move(square("c7"), square("c5"));
+        assert_eq!(Some(square("c6")), board.en_passant_target());
+        assert_eq!(
+            "rnbqkbnr/pp1ppppp/8/2p5/4P3/8/PPPP1PPP/RNBQKBNR w KQkq c6 0 2",
+            board.into_fen()
+        );
+
+        board.play_move(square("g1"), square("f3"));
+        assert_eq!(None, board.en_passant_target());
+    }
+
+    #[test]
+    fn test_pawn_double_move_restrictions() {
+        let targets = |board: &Board, from: &str| {
+            board
+                .moves_from(square(from))
+                .into_iter()
+                .map(|mv| mv.to)
+                .collect::<Vec<_>>()
+        };
+
+        // The c2 pawn can't jump the knight on c3, the e3 pawn has left its starting rank
+        let board = Board::from_fen("4k3/8/8/8/8/2n1P3/2P5/4K3 w - - 0 1");
+        assert!(targets(&board, "c2").is_empty());
+        assert_eq!(vec![square("e4")], targets(&board, "e3"));
+
+        let board = Board::from_fen("k7/3p4/1p1N4/8/8/8/8/4K3 b - - 0 1");
+        assert!(targets(&board, "d7").is_empty());
+        assert_eq!(vec![square("b5")], targets(&board, "b6"));
+    }
+
+    #[test]
+    fn test_en_passant_sequence_for_both_colors() {
+        let mut board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+        for (from, to) in &[("e2", "e4"), ("a7", "a6"), ("e4", "e5"), ("d7", "d5")] {
+            assert_eq!(MoveResult::Ok, board.play_move(square(from), square(to)));
+        }
+
+        assert_eq!(Some(square("d6")), board.en_passant_target());
+        assert_eq!(MoveResult::Ok, board.play_move(square("e5"), square("d6")));
+        assert!(board.piece_at(square("d5")).is_none());
+        assert_eq!(
+            "rnbqkbnr/1pp1pppp/p2P4/8/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 3",
+            board.into_fen()
+        );
+
+        let mut board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+        for (from, to) in &[
+            ("a2", "a3"),
+            ("e7", "e5"),
+            ("a3", "a4"),
+            ("e5", "e4"),
+            ("d2", "d4"),
+        ] {
+            assert_eq!(MoveResult::Ok, board.play_move(square(from), square(to)));
+        }
+
+        assert_eq!(Some(square("d3")), board.en_passant_target());
+        assert_eq!(MoveResult::Ok, board.play_move(square("e4"), square("d3")));
+        assert!(board.piece_at(square("d4")).is_none());
+        assert_eq!(
+            "rnbqkbnr/pppp1ppp/8/8/P7/3p4/1PP1PPPP/RNBQKBNR w KQkq - 0 4",
+            board.into_fen()
+        );
+    }
+
+    #[test]
+    fn test_attackers_of_type() {
+        let board = Board::from_fen("4k3/8/8/3q4/1N6/2P1N3/8/3RK1n1 w - - 0 1");
+
+        assert_eq!(
+            vec![square("e3"), square("b4")],
+            board.attackers_of_type(square("d5"), Color::White, PieceType::Knight)
+        );
+        assert_eq!(
+            vec![square("d1"), square("e3"), square("b4")],
+            board.attackers_of(square("d5"), Color::White)
+        );
+        assert!(board
+            .attackers_of_type(square("d5"), Color::White, PieceType::Bishop)
+            .is_empty());
+        assert!(board
+            .attackers_of_type(square("e3"), Color::White, PieceType::Knight)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_promotion_policy_auto_queen() {
+        let mut board = Board::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+        assert_eq!(PromotionPolicy::AutoQueen, board.promotion_policy());
+        assert_eq!(None, board.promotion_choices());
+
+        assert_eq!(MoveResult::Ok, board.play_move(square("a7"), square("a8")));
+        assert_eq!(
+            Some(PieceType::Queen),
+            board.piece_at(square("a8")).map(|p| p.piece_type())
+        );
+        assert_eq!(
+            Some(Move::with_promotion(
+                square("a7"),
+                square("a8"),
+                PieceType::Queen
+            )),
+            board.last_move()
+        );
+    }
+
+    #[test]
+    fn test_promotion_policy_prompt() {
+        let mut board = Board::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+        board.set_promotion_policy(PromotionPolicy::Prompt);
+
+        assert_eq!(
+            MoveResult::PawnPromote,
+            board.play_move(square("a7"), square("a8"))
+        );
+        assert_eq!(
+            Some(PieceType::Pawn),
+            board.piece_at(square("a8")).map(|p| p.piece_type())
+        );
+
+        assert_eq!(Some(square("a8")), board.pending_promotion());
+        assert_eq!(Some(PROMOTION_PIECE_TYPES), board.promotion_choices());
+
+        board.change_piece_type(square("a8"), PieceType::Knight);
+        assert_eq!(None, board.promotion_choices());
+        assert_eq!(
+            Some(PieceType::Knight),
+            board.piece_at(square("a8")).map(|p| p.piece_type())
+        );
+    }
+
+    #[test]
+    fn test_play_move_with_promotion() {
+        let mut board = Board::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+        board.set_promotion_policy(PromotionPolicy::Prompt);
+
+        for invalid in &[PieceType::King, PieceType::Pawn] {
+            assert_eq!(
+                MoveResult::Invalid,
+                board.play_move_with_promotion(square("a7"), square("a8"), *invalid)
+            );
+        }
+
+        assert_eq!(
+            MoveResult::Ok,
+            board.play_move_with_promotion(square("a7"), square("a8"), PieceType::Knight)
+        );
+        assert!(board.is_occupied_by(square("a8"), PieceType::Knight, Color::White));
+        assert_eq!(None, board.pending_promotion());
+
+        assert_eq!(
+            MoveResult::Ok,
+            board.play_move_with_promotion(square("e8"), square("d7"), PieceType::King)
+        );
+        assert!(board.is_occupied_by(square("d7"), PieceType::King, Color::Black));
+    }
+
+    #[test]
+    fn test_promotion_resets_half_move_clock() {
+        let fen = "4k3/P7/8/8/8/8/8/4K3 w - - 12 40";
+
+        let mut board = Board::from_fen(fen);
+        assert_eq!(MoveResult::Ok, board.play_move(square("a7"), square("a8")));
+        assert_eq!(0, board.half_moves());
+
+        let mut board = Board::from_fen(fen);
+        board.set_promotion_policy(PromotionPolicy::Prompt);
+        assert_eq!(
+            MoveResult::PawnPromote,
+            board.play_move(square("a7"), square("a8"))
+        );
+        board.change_piece_type(square("a8"), PieceType::Rook);
+        assert_eq!(0, board.half_moves());
+
+        let mut board = Board::from_fen(fen);
+        assert_eq!(
+            MoveResult::Ok,
+            board.play(Move::with_promotion(
+                square("a7"),
+                square("a8"),
+                PieceType::Knight
+            ))
+        );
+        assert_eq!(0, board.half_moves());
+    }
+
+    #[test]
+    fn test_with_builders() {
+        let board = Board::from_fen("r3k2r/8/8/8/4Pp2/8/8/R3K2R w KQkq - 0 1")
+            .with_active_color(Color::Black)
+            .with_castling(Color::White, Castling::none())
+            .with_castling(
+                Color::Black,
+                Castling {
+                    kingside: true,
+                    queenside: false,
+                },
+            )
+            .with_en_passant(Some(square("e3")));
+
+        assert_eq!("r3k2r/8/8/8/4Pp2/8/8/R3K2R b k e3 0 1", board.into_fen());
+        assert_eq!(
+            Some(square("e3")),
+            board
+                .with_en_passant(Some(square("e4")))
+                .en_passant_target()
+        );
+    }
+
+    #[test]
+    fn test_null_move() {
+        let fen = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1";
+        let mut board = Board::from_fen(fen);
+        let hash = board.zobrist_hash();
+
+        let undo = board.make_null_move();
+        assert_eq!(Color::White, board.active_color());
+        assert_eq!(None, board.en_passant_target());
+        assert_ne!(hash, board.zobrist_hash());
+
+        board.unmake_null_move(undo);
+        assert_eq!(fen, board.into_fen());
+        assert_eq!(hash, board.zobrist_hash());
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let mut board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        board.play_move(square("e2"), square("e4"));
+
+        let snapshot = board.snapshot();
+        let fen = board.into_fen();
+
+        board.play_move(square("e7"), square("e5"));
+        board.play_move(square("e1"), square("e2"));
+        assert_ne!(fen, board.into_fen());
+
+        board.restore(snapshot);
+        assert_eq!(fen, board.into_fen());
+        assert_eq!(
+            Some(Move::new(square("e2"), square("e4"))),
+            board.last_move()
+        );
+        assert!(board.white_castling().kingside);
+    }
+
+    #[test]
+    fn test_legal_moves_of_type() {
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+
+        let mut castling = board.legal_moves_of_type(MoveType::Castling);
+        castling.sort();
+
+        assert_eq!(
+            vec![
+                Move::new(square("e1"), square("c1")),
+                Move::new(square("e1"), square("g1")),
+            ],
+            castling
+        );
+
+        let board = Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1");
+        assert_eq!(
+            vec![Move::new(square("e5"), square("d6"))],
+            board.legal_moves_of_type(MoveType::EnPassant)
+        );
+        assert!(board.legal_moves_of_type(MoveType::PawnCapture).is_empty());
+    }
+
+    #[test]
+    fn test_is_same_position_as() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/5N2/PPPPPPPP/RNBQKB1R b KQkq - 1 1");
+        let other = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/5N2/PPPPPPPP/RNBQKB1R b KQkq - 7 5");
+
+        assert!(board.is_same_position_as(&other));
+
+        let other = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/5N2/PPPPPPPP/RNBQKB1R w KQkq - 1 1");
+        assert!(!board.is_same_position_as(&other));
+
+        let other = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/5N2/PPPPPPPP/RNBQKB1R b Qkq - 1 1");
+        assert!(!board.is_same_position_as(&other));
+    }
+
+    #[test]
+    fn test_can_move() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+        assert!(board.can_move(square("g1")));
+        assert!(board.can_move(square("e2")));
+        assert!(!board.can_move(square("a1")));
+        assert!(!board.can_move(square("c1")));
+        assert!(!board.can_move(square("g8")));
+        assert!(!board.can_move(square("e4")));
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/4R3/r3K3 w - - 0 1");
+        assert!(!board.can_move(square("e2")));
+    }
+
+    #[test]
+    fn test_moves_from() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+        let mut moves = board.moves_from(square("g1"));
+        moves.sort();
+
+        assert_eq!(
+            vec![
+                Move::new(square("g1"), square("f3")),
+                Move::new(square("g1"), square("h3")),
+            ],
+            moves
+        );
+        assert!(board.moves_from(square("g8")).is_empty());
+        assert!(board.moves_from(square("e4")).is_empty());
+
+        let board = Board::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+        let moves = board.moves_from(square("a7"));
+
+        assert_eq!(4, moves.len());
+        assert!(moves
+            .iter()
+            .all(|mv| mv.from == square("a7") && mv.promotion.is_some()));
+    }
+
+    #[test]
+    fn test_annotated_moves() {
+        let board = Board::from_fen("4r1k1/8/8/8/8/8/4B3/3QK3 w - - 0 1");
+        let moves = board.annotated_moves(square("e2"));
+
+        assert_eq!(9, moves.len());
+        assert!(moves.iter().all(
+            |(_, result)| *result == Err(MoveError::WouldLeaveKingInCheck)
+                || *result == Err(MoveError::OccupiedByOwnPiece)
+        ));
+        assert!(moves.contains(&(square("d1"), Err(MoveError::OccupiedByOwnPiece))));
+        assert!(moves.contains(&(square("a6"), Err(MoveError::WouldLeaveKingInCheck))));
+
+        let moves = board.annotated_moves(square("d1"));
+        assert!(moves.contains(&(square("d8"), Ok(()))));
+        assert!(moves.contains(&(square("e1"), Err(MoveError::OccupiedByOwnPiece))));
+        assert!(moves.contains(&(square("f3"), Err(MoveError::Blocked))));
+
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let moves = board.annotated_moves(square("c1"));
+        assert!(moves.contains(&(square("d2"), Err(MoveError::OccupiedByOwnPiece))));
+        assert!(moves.contains(&(square("e3"), Err(MoveError::Blocked))));
+
+        assert!(board
+            .annotated_moves(square("e7"))
+            .iter()
+            .all(|(_, result)| *result == Err(MoveError::NotYourTurn)));
+    }
+
+    #[test]
+    fn test_legal_destinations_bitboard() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+        assert_eq!(
+            1 << square("a3").to_index() | 1 << square("c3").to_index(),
+            board.legal_destinations_bitboard(square("b1"))
+        );
+        assert_eq!(
+            0x0000_0000_0005_0000,
+            board.legal_destinations_bitboard(square("b1"))
+        );
+        assert_eq!(0, board.legal_destinations_bitboard(square("a1")));
+        assert_eq!(0, board.legal_destinations_bitboard(square("b8")));
+    }
+
+    #[test]
+    fn test_legal_targets() {
+        let board = Board::from_fen("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+
+        let mut targets = board.legal_targets(square("a7"));
+        targets.sort();
+
+        assert_eq!(vec![(square("a8"), true), (square("b8"), true)], targets);
+
+        let targets = board.legal_targets(square("e1"));
+        assert_eq!(5, targets.len());
+        assert!(targets.iter().all(|(_, promotion)| !promotion));
+
+        assert!(board.legal_targets(square("b8")).is_empty());
+    }
+
+    #[test]
+    fn test_is_promotion() {
+        let board = Board::from_fen("k7/4P3/8/8/8/8/4P3/4K3 w - - 0 1");
+
+        assert!(board.is_promotion(square("e7"), square("e8")));
+        assert!(!board.is_promotion(square("e2"), square("e4")));
+        assert!(!board.is_promotion(square("e7"), square("d8")));
+        assert!(!board.is_promotion(square("e1"), square("d1")));
+
+        let board = Board::from_fen("4k3/4P3/8/8/8/8/8/4K3 w - - 0 1");
+        assert!(!board.is_promotion(square("e7"), square("e8")));
+    }
+
+    #[test]
+    fn test_resolve_move() {
+        let board = Board::from_fen("k7/4P3/8/8/8/8/8/4K1N1 w - - 0 1");
+
+        assert_eq!(
+            Some(Move::new(square("g1"), square("f3"))),
+            board.resolve_move(square("g1"), square("f3"))
+        );
+        assert_eq!(None, board.resolve_move(square("g1"), square("g3")));
+        assert_eq!(None, board.resolve_move(square("a8"), square("a7")));
+
+        let promotion = board.resolve_move(square("e7"), square("e8")).unwrap();
+        assert_eq!(None, promotion.promotion);
+        assert!(board.is_promotion(promotion.from, promotion.to));
+    }
+
+    #[test]
+    fn test_status() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert_eq!(GameStatus::Ongoing, board.status());
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4R1K1 b - - 0 1");
+        assert_eq!(GameStatus::Check, board.status());
+
+        let board =
+            Board::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3");
+        assert_eq!(GameStatus::Checkmate, board.status());
+
+        let board = Board::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1");
+        assert_eq!(GameStatus::Stalemate, board.status());
+
+        // The only piece that could move is pinned
+        let board = Board::from_fen("k1K5/1r6/1P6/8/4B3/8/8/8 b - - 0 1");
+        assert_eq!(GameStatus::Stalemate, board.status());
+
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4R1K1 b - - 0 1");
+        board.set_result(GameResult::WhiteWins);
+        assert_eq!(GameStatus::Decided(GameResult::WhiteWins), board.status());
+    }
+
+    #[test]
+    fn test_moves_avoiding_stalemate() {
+        let board = Board::from_fen("k7/8/1K6/8/8/8/8/2Q5 w - - 0 1");
+        let stalemate = Move::new(square("c1"), square("c7"));
+
+        let mut after = board;
+        after.play(stalemate);
+        assert!(after.is_stalemate());
+
+        let moves = board.moves_avoiding_stalemate();
+        assert!(!moves.contains(&stalemate));
+        assert!(!moves.contains(&Move::new(square("c1"), square("f4"))));
+        assert!(moves.contains(&Move::new(square("c1"), square("c8"))));
+        assert_eq!(board.all_legal_moves().len() - 2, moves.len());
+    }
+
+    #[test]
+    fn test_parse_coordinate_move() {
+        let board = Board::from_fen("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2");
+        let d2d3 = Some(Move::new(square("d2"), square("d3")));
+        let exd5 = Some(Move::new(square("e4"), square("d5")));
+
+        assert_eq!(d2d3, board.parse_coordinate_move("d2d3"));
+        assert_eq!(d2d3, board.parse_coordinate_move("d2-d3"));
+        assert_eq!(d2d3, board.parse_coordinate_move("d2 d3"));
+        assert_eq!(exd5, board.parse_coordinate_move("e4xd5"));
+        assert_eq!(exd5, board.parse_coordinate_move(" e4-d5 "));
+
+        assert_eq!(None, board.parse_coordinate_move("d2-d5"));
+        assert_eq!(None, board.parse_coordinate_move("d2--d3"));
+        assert_eq!(None, board.parse_coordinate_move("d7-d6"));
+        assert_eq!(None, board.parse_coordinate_move("d2"));
+
+        let board = Board::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+        assert_eq!(
+            Some(Move::with_promotion(
+                square("a7"),
+                square("a8"),
+                PieceType::Knight
+            )),
+            board.parse_coordinate_move("a7-a8n")
+        );
+        assert_eq!(None, board.parse_coordinate_move("a7-a8"));
+    }
+
+    #[test]
+    fn test_last_move_was_check() {
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1");
+        assert!(board.last_move().is_none());
+        assert!(!board.last_move_was_check());
+
+        board.play(Move::new(square("a1"), square("a7")));
+        assert!(!board.last_move_was_check());
+
+        board.play(Move::new(square("e8"), square("f8")));
+        board.play(Move::new(square("a7"), square("a8")));
+        assert_eq!(
+            Some(Move::new(square("a7"), square("a8"))),
+            board.last_move()
+        );
+        assert!(board.last_move_was_check());
+    }
+
+    #[test]
+    fn test_capture_promotions() {
+        let board = Board::from_fen("nrb1k3/1P6/8/8/8/8/8/4K3 w - - 0 1");
+
+        let promotions = board
+            .all_legal_moves()
+            .into_iter()
+            .filter(|mv| mv.promotion.is_some())
+            .collect::<Vec<_>>();
+
+        assert_eq!(8, promotions.len());
+
+        for target in &["a8", "c8"] {
+            let to_target = promotions.iter().filter(|mv| mv.to == square(target));
+
+            assert_eq!(
+                PROMOTION_PIECE_TYPES.to_vec(),
+                to_target.filter_map(|mv| mv.promotion).collect::<Vec<_>>()
+            );
+        }
+
+        let capture = Move::with_promotion(square("b7"), square("a8"), PieceType::Queen);
+        assert!(board.is_capture(&capture));
+        assert_eq!(Some("bxa8=Q".to_string()), board.move_to_san(&capture));
+    }
+
+    #[test]
+    fn test_legal_capture_count() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert_eq!(0, board.legal_capture_count());
+
+        let board = Board::from_fen("4k3/8/3p1n2/4P3/8/8/r7/R3K3 w - - 0 1");
+        assert_eq!(3, board.legal_capture_count());
+        assert_eq!(
+            board.legal_capture_count(),
+            board
+                .all_legal_moves()
+                .iter()
+                .filter(|mv| board.is_capture(mv))
+                .count()
+        );
+    }
+
+    #[test]
+    fn test_play_random_move() {
+        let mut board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+        for seed in 0..40 {
+            let color = board.active_color();
+
+            match board.play_random_move(seed) {
+                Some(_) => assert_ne!(color, board.active_color()),
+                None => break,
+            }
+        }
+
+        let mut mated =
+            Board::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3");
+        assert_eq!(None, mated.play_random_move(0));
+    }
+
+    #[test]
+    fn test_valid_moves_fast_path_matches_simulation() {
+        let start =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
+
+        for game in 0..8 {
+            let mut board = start;
+
+            for ply in 0..30 {
+                for from in Square::all() {
+                    let piece = match board.piece_at(from) {
+                        Some(piece) if piece.color() == board.active_color() => piece,
+                        _ => continue,
+                    };
+
+                    let fast = board
+                        .valid_moves(&piece, &from, true)
+                        .iter()
+                        .map(|valid_move| valid_move.target)
+                        .collect::<Vec<_>>();
+
+                    let simulated = board
+                        .valid_moves(&piece, &from, false)
+                        .iter()
+                        .filter(|valid_move| {
+                            valid_move.move_type != MoveType::Castling
+                                || !board.is_in_check()
+                                    && !board.is_square_attacked(
+                                        ((from.x + valid_move.target.x) / 2, from.y),
+                                        piece.color().opposite(),
+                                    )
+                        })
+                        .filter(|valid_move| {
+                            !board
+                                .board_after(&piece, from, valid_move.target)
+                                .is_king_threatened(piece.color())
+                        })
+                        .map(|valid_move| valid_move.target)
+                        .collect::<Vec<_>>();
+
+                    assert_eq!(simulated, fast, "{} from {}", board.into_fen(), from);
+                }
+
+                if board.play_random_move(game * 1_000 + ply).is_none() {
+                    break;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_castling() {
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+        let king_moves = board
+            .all_legal_moves()
+            .into_iter()
+            .filter(|mv| mv.from == square("e1"))
+            .count();
+        assert_eq!(7, king_moves);
+
+        // The king may not pass through the attacked f1 square
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/5r2/R3K2R w KQkq - 0 1");
+        assert!(board.is_legal(&Move::new(square("e1"), square("c1"))));
+        assert!(!board.is_legal(&Move::new(square("e1"), square("g1"))));
+
+        // The b1 square may be attacked when castling queenside
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/1r6/R3K2R w KQkq - 0 1");
+        assert!(board.is_legal(&Move::new(square("e1"), square("c1"))));
+
+        // Castling rights are required
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w Qkq - 0 1");
+        assert!(!board.is_legal(&Move::new(square("e1"), square("g1"))));
+        assert!(board.is_legal(&Move::new(square("e1"), square("c1"))));
+    }
+
+    #[test]
+    fn test_chess960_castling() {
+        let mut board = Board::from_fen("1r4kr/8/8/8/8/8/8/1R4KR w KQkq - 0 1");
+
+        assert_eq!(
+            Some(square("h1")),
+            board.castling_rook(Color::White, CastleSide::Kingside)
+        );
+        assert_eq!(
+            Some(square("b1")),
+            board.castling_rook(Color::White, CastleSide::Queenside)
+        );
+        assert!(board.is_legal_position());
+
+        // The king already stands on its kingside destination, so castling is written as the
+        // king moving onto the rook
+        let mut castling = board.legal_moves_of_type(MoveType::Castling);
+        castling.sort();
+        assert_eq!(
+            vec![
+                Move::new(square("g1"), square("b1")),
+                Move::new(square("g1"), square("h1"))
+            ],
+            castling
+        );
+        assert!(board.is_legal(&Move::new(square("g1"), square("f1"))));
+
+        assert_eq!(MoveResult::Ok, board.play_move(square("g1"), square("h1")));
+        assert!(board.is_occupied_by(square("g1"), PieceType::King, Color::White));
+        assert!(board.is_occupied_by(square("f1"), PieceType::Rook, Color::White));
+        assert!(board.piece_at(square("h1")).is_none());
+        assert_eq!("1r4kr/8/8/8/8/8/8/1R3RK1 b kq - 1 1", board.into_fen());
+
+        let mut board = Board::from_fen("r1k4r/8/8/8/8/8/8/1R4KR b KQkq - 0 1");
+
+        assert_eq!(
+            MoveResult::Ok,
+            board.castle(Color::Black, CastleSide::Queenside)
+        );
+        assert!(board.is_occupied_by(square("c8"), PieceType::King, Color::Black));
+        assert!(board.is_occupied_by(square("d8"), PieceType::Rook, Color::Black));
+        assert!(board.piece_at(square("a8")).is_none());
+
+        // The king may not pass d1, now attacked by the rook on d8
+        assert_eq!(
+            MoveResult::Invalid,
+            board.castle(Color::White, CastleSide::Queenside)
+        );
+        assert_eq!(
+            MoveResult::Ok,
+            board.castle(Color::White, CastleSide::Kingside)
+        );
+    }
+
+    #[test]
+    fn test_castle() {
+        let mut board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+
+        assert_eq!(
+            MoveResult::Invalid,
+            board.castle(Color::Black, CastleSide::Kingside)
+        );
+        assert_eq!(
+            MoveResult::Ok,
+            board.castle(Color::White, CastleSide::Kingside)
+        );
+        assert!(board.is_occupied_by(square("g1"), PieceType::King, Color::White));
+        assert!(board.is_occupied_by(square("f1"), PieceType::Rook, Color::White));
+
+        assert_eq!(
+            MoveResult::Ok,
+            board.castle(Color::Black, CastleSide::Queenside)
+        );
+        assert!(board.is_occupied_by(square("c8"), PieceType::King, Color::Black));
+        assert!(board.is_occupied_by(square("d8"), PieceType::Rook, Color::Black));
+
+        // f1 is attacked by the rook on f8
+        let mut board = Board::from_fen("r3kr2/8/8/8/8/8/8/R3K2R w KQq - 0 1");
+        assert_eq!(
+            MoveResult::Invalid,
+            board.castle(Color::White, CastleSide::Kingside)
+        );
+        assert!(board.is_occupied_by(square("e1"), PieceType::King, Color::White));
+        assert_eq!(
+            MoveResult::Ok,
+            board.castle(Color::White, CastleSide::Queenside)
+        );
+    }
+
+    #[test]
+    fn test_castling_rights_lost_by_rook_capture() {
+        let mut board = Board::from_fen("r3k2r/1B6/8/8/8/8/8/4K3 w kq - 0 1");
+
+        assert_eq!(MoveResult::Ok, board.play_move(square("b7"), square("a8")));
+
+        assert_eq!(
+            vec![CastlingRightsChange {
+                color: Color::Black,
+                lost: Castling {
+                    kingside: false,
+                    queenside: true,
+                },
+                reason: CastlingLossReason::RookCaptured,
+            }],
+            board.castling_rights_changed()
+        );
+        assert!(board.black_castling().kingside);
+        assert!(!board.black_castling().queenside);
+    }
+
+    #[test]
+    fn test_castling_rights_lost_by_king_move() {
+        let mut board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+
+        assert_eq!(MoveResult::Ok, board.play_move(square("e1"), square("e2")));
+
+        assert_eq!(
+            vec![CastlingRightsChange {
+                color: Color::White,
+                lost: Castling::both(),
+                reason: CastlingLossReason::KingMoved,
+            }],
+            board.castling_rights_changed()
+        );
+
+        assert_eq!(MoveResult::Ok, board.play_move(square("a8"), square("a7")));
+        assert_eq!(MoveResult::Ok, board.play_move(square("e2"), square("e3")));
+
+        assert!(board.castling_rights_changed().is_empty());
+    }
+
+    #[test]
+    fn test_rook_capture_removes_opponent_castling() {
+        let mut board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+
+        assert_eq!(MoveResult::Ok, board.play_move(square("h1"), square("h8")));
+        assert_eq!("r3k2R/8/8/8/8/8/8/R3K3 b Qq - 0 1", board.into_fen());
+    }
+
+    #[test]
+    fn test_rook_move_removes_own_castling_side() {
+        let mut board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1");
+
+        assert_eq!(MoveResult::Ok, board.play_move(square("a1"), square("a2")));
+        assert_eq!("r3k2r/8/8/8/8/8/R7/4K2R b Kkq - 1 1", board.into_fen());
+
+        assert_eq!(MoveResult::Ok, board.play_move(square("h8"), square("h7")));
+        assert_eq!("r3k3/7r/8/8/8/8/R7/4K2R w Kq - 2 2", board.into_fen());
+    }
+}
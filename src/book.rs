@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use crate::{board::Board, rule::Move};
+
+/// A source of opening moves keyed by position hash (see [`Board::zobrist_hash`]).
+pub trait OpeningBook {
+    /// Weighted book moves for the position with the given hash.
+    fn lookup(&self, key: u64) -> Option<Vec<(Move, u32)>>;
+}
+
+/// In-memory opening book.
+#[derive(Debug, Clone, Default)]
+pub struct HashMapBook {
+    entries: HashMap<u64, Vec<(Move, u32)>>,
+}
+
+impl HashMapBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a weighted move for the position with the given hash.
+    pub fn insert(&mut self, key: u64, mv: Move, weight: u32) {
+        self.entries.entry(key).or_default().push((mv, weight));
+    }
+
+    /// Add a weighted move for the given position.
+    pub fn insert_position(&mut self, board: &Board, mv: Move, weight: u32) {
+        self.insert(board.zobrist_hash(), mv, weight);
+    }
+}
+
+impl OpeningBook for HashMapBook {
+    fn lookup(&self, key: u64) -> Option<Vec<(Move, u32)>> {
+        self.entries.get(&key).cloned()
+    }
+}
+
+impl Board {
+    /// The highest weighted legal book move for the current position, if the book has one.
+    pub fn book_move(&self, book: &impl OpeningBook) -> Option<Move> {
+        book.lookup(self.zobrist_hash())?
+            .into_iter()
+            .filter(|(mv, _)| self.is_legal(mv))
+            .max_by_key(|(_, weight)| *weight)
+            .map(|(mv, _)| mv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::*;
+    use crate::{fen::FromFen, square::Square};
+
+    #[test]
+    fn test_book_move() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+        let e4 = Move::new(
+            Square::try_from("e2").unwrap(),
+            Square::try_from("e4").unwrap(),
+        );
+        let d4 = Move::new(
+            Square::try_from("d2").unwrap(),
+            Square::try_from("d4").unwrap(),
+        );
+
+        let mut book = HashMapBook::new();
+        book.insert_position(&board, d4, 10);
+        book.insert_position(&board, e4, 20);
+
+        assert_eq!(Some(e4), board.book_move(&book));
+        assert_eq!(None, Board::empty().book_move(&book));
+    }
+}
@@ -0,0 +1,225 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{board::Board, rule::Move, square::Square};
+
+/// Memoized legal moves of recently queried positions.
+///
+/// [`Board`] is `Copy` and can't own the cached moves, so the cache is kept next to the board.
+/// Entries are keyed by [`Board::zobrist_hash`], which changes with any change to the
+/// position, so a cache never returns the moves of a position that has since been modified.
+///
+/// The cache holds the moves of one position by default. With a larger capacity, positions
+/// reached again through transpositions are served from the cache, and the least recently
+/// used position is evicted when the cache is full.
+#[derive(Debug, Clone)]
+pub struct LegalMoveCache {
+    /// Moves by position hash, with the tick of the last lookup.
+    entries: HashMap<u64, (Vec<Move>, u64)>,
+    /// Position hashes by the tick of their last lookup, least recently used first.
+    recency: BTreeMap<u64, u64>,
+    capacity: usize,
+    tick: u64,
+}
+
+impl Default for LegalMoveCache {
+    fn default() -> Self {
+        Self::with_capacity(1)
+    }
+}
+
+impl LegalMoveCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A cache holding the moves of up to `capacity` positions, at least one.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            capacity: capacity.max(1),
+            tick: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Change the number of positions kept, at least one. Least recently used positions are
+    /// evicted if the cache holds more than that.
+    ///
+    /// The capacity is set here rather than on [`Board`], since the board is `Copy` and can't
+    /// own the cached moves it would bound.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+
+        while self.entries.len() > self.capacity {
+            self.evict_least_recently_used();
+        }
+    }
+
+    /// Number of positions currently cached.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Whether the legal moves of the given position are cached.
+    pub fn is_cached(&self, board: &Board) -> bool {
+        self.entries.contains_key(&board.zobrist_hash())
+    }
+
+    /// Legal moves of the given position, generated only if the position isn't cached.
+    pub fn legal_moves(&mut self, board: &Board) -> &[Move] {
+        let key = board.zobrist_hash();
+
+        if !self.entries.contains_key(&key) && self.entries.len() == self.capacity {
+            self.evict_least_recently_used();
+        }
+
+        self.tick += 1;
+        let tick = self.tick;
+
+        let entry = self
+            .entries
+            .entry(key)
+            .or_insert_with(|| (board.all_legal_moves(), tick));
+
+        self.recency.remove(&entry.1);
+        self.recency.insert(tick, key);
+        entry.1 = tick;
+
+        &entry.0
+    }
+
+    /// Cached legal moves of the piece on the given square.
+    pub fn legal_moves_from(&mut self, board: &Board, square: impl Into<Square>) -> Vec<Move> {
+        let square = square.into();
+
+        self.legal_moves(board)
+            .iter()
+            .filter(|mv| mv.from == square)
+            .copied()
+            .collect()
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        let oldest = self.recency.iter().next().map(|(tick, key)| (*tick, *key));
+
+        if let Some((tick, key)) = oldest {
+            self.recency.remove(&tick);
+            self.entries.remove(&key);
+        }
+    }
+}
+
+impl Board {
+    /// A legal move cache filled with the moves of this position.
+    pub fn legal_move_cache(&self) -> LegalMoveCache {
+        let mut cache = LegalMoveCache::new();
+        cache.legal_moves(self);
+        cache
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::*;
+    use crate::fen::FromFen;
+
+    #[test]
+    fn test_cache_invalidated_by_move() {
+        let mut board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let mut cache = board.legal_move_cache();
+
+        assert!(cache.is_cached(&board));
+        assert_eq!(board.all_legal_moves(), cache.legal_moves(&board));
+
+        let e2 = Square::try_from("e2").unwrap();
+        assert_eq!(2, cache.legal_moves_from(&board, e2).len());
+
+        board.play_move(e2, Square::try_from("e4").unwrap());
+
+        assert!(!cache.is_cached(&board));
+        assert_eq!(board.all_legal_moves(), cache.legal_moves(&board));
+        assert!(cache.is_cached(&board));
+        assert!(cache.legal_moves(&board).iter().all(|mv| mv.from.y >= 6));
+    }
+
+    #[test]
+    fn test_cache_capacity() {
+        let start = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let mut after_e4 = start;
+        after_e4.play_move((4, 1), (4, 3));
+        let mut after_d4 = start;
+        after_d4.play_move((3, 1), (3, 3));
+
+        let mut cache = LegalMoveCache::with_capacity(2);
+        let moves = cache.legal_moves(&start).to_vec();
+        cache.legal_moves(&after_e4);
+
+        assert_eq!(2, cache.len());
+        assert!(cache.is_cached(&start));
+        assert_eq!(moves, cache.legal_moves(&start));
+
+        // after_e4 is now the least recently used position
+        cache.legal_moves(&after_d4);
+        assert_eq!(2, cache.len());
+        assert!(cache.is_cached(&start));
+        assert!(!cache.is_cached(&after_e4));
+        assert!(cache.is_cached(&after_d4));
+
+        cache.set_capacity(1);
+        assert_eq!(1, cache.len());
+        assert!(cache.is_cached(&after_d4));
+
+        cache.set_capacity(0);
+        assert_eq!(1, cache.capacity());
+    }
+
+    #[test]
+    fn test_cache_eviction_order() {
+        let start = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let positions = start
+            .all_legal_moves()
+            .into_iter()
+            .map(|mv| {
+                let mut board = start;
+                board.play(mv);
+                board
+            })
+            .collect::<Vec<_>>();
+
+        let mut cache = LegalMoveCache::with_capacity(8);
+
+        for board in &positions[..8] {
+            cache.legal_moves(board);
+        }
+
+        // Touch the oldest half again so the other half is evicted first
+        for board in &positions[..4] {
+            cache.legal_moves(board);
+        }
+
+        for board in &positions[8..12] {
+            cache.legal_moves(board);
+        }
+
+        assert_eq!(8, cache.len());
+        assert!(positions[..4].iter().all(|board| cache.is_cached(board)));
+        assert!(positions[4..8].iter().all(|board| !cache.is_cached(board)));
+        assert!(positions[8..12].iter().all(|board| cache.is_cached(board)));
+
+        cache.set_capacity(6);
+        assert_eq!(6, cache.len());
+        assert!(!cache.is_cached(&positions[0]));
+        assert!(!cache.is_cached(&positions[1]));
+        assert!(positions[2..4].iter().all(|board| cache.is_cached(board)));
+    }
+}
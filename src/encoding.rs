@@ -0,0 +1,42 @@
+use crate::{board::Board, square::Square};
+
+impl Board {
+    /// Encode the piece placement as twelve 8x8 planes for neural network input.
+    ///
+    /// Planes are ordered by [`Piece::index`](crate::piece::Piece::index): white pawns,
+    /// knights, bishops, rooks, queens and king in planes 0 to 5, followed by the black
+    /// pieces in the same order in planes 6 to 11. Each plane is indexed `[rank][file]`
+    /// from White's side, so `planes[p][0][0]` is a1 and `planes[p][7][7]` is h8.
+    /// A cell is `1.0` when a piece of the plane's type and color stands on it.
+    pub fn to_planes(&self) -> [[[f32; 8]; 8]; 12] {
+        let mut planes = [[[0.0; 8]; 8]; 12];
+
+        for square in Square::all() {
+            if let Some(piece) = self.piece(square.x, square.y) {
+                planes[piece.index()][square.y as usize][square.x as usize] = 1.0;
+            }
+        }
+
+        planes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fen::FromFen;
+
+    #[test]
+    fn test_to_planes() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let planes = board.to_planes();
+
+        let count = |plane: &[[f32; 8]; 8]| plane.iter().flatten().filter(|c| **c == 1.0).count();
+
+        assert_eq!(8, count(&planes[0]));
+        assert!(planes[0][1].iter().all(|c| *c == 1.0));
+        assert_eq!(1.0, planes[5][0][4]);
+        assert_eq!(1.0, planes[11][7][4]);
+        assert_eq!(32, planes.iter().map(count).sum::<usize>());
+    }
+}
@@ -0,0 +1,131 @@
+use crate::{board::Board, color::Color, rule::Move};
+
+/// Score of a checkmate, reduced by the number of plies to the mate so that shorter mates
+/// score higher.
+pub const MATE_SCORE: i32 = 1_000_000;
+
+/// Static evaluation of a position, used by [`Board::best_move`].
+pub trait Evaluator {
+    /// Score of the position in centipawns from White's point of view: positive when White
+    /// is better and negative when Black is.
+    fn evaluate(&self, board: &Board) -> i32;
+}
+
+/// Evaluates a position by its [material balance](Board::material_balance) only.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MaterialEvaluator;
+
+impl Evaluator for MaterialEvaluator {
+    fn evaluate(&self, board: &Board) -> i32 {
+        board.material_balance()
+    }
+}
+
+impl Board {
+    /// The best move for the side to move found by searching every line of `depth` plies
+    /// and scoring the resulting positions with the evaluator. `None` if there are no legal
+    /// moves or the depth is 0.
+    pub fn best_move(&self, depth: u32, evaluator: &impl Evaluator) -> Option<Move> {
+        if depth == 0 {
+            return None;
+        }
+
+        let mut best = None;
+        let mut alpha = -MATE_SCORE - 1;
+
+        for mv in self.all_legal_moves() {
+            let mut board = *self;
+            board.play(mv);
+
+            let score = -board.negamax(depth - 1, 1, -MATE_SCORE - 1, -alpha, evaluator);
+
+            if best.is_none() || score > alpha {
+                alpha = score;
+                best = Some(mv);
+            }
+        }
+
+        best
+    }
+
+    fn negamax(
+        &self,
+        depth: u32,
+        ply: i32,
+        mut alpha: i32,
+        beta: i32,
+        evaluator: &impl Evaluator,
+    ) -> i32 {
+        let moves = self.all_legal_moves();
+
+        if moves.is_empty() {
+            return if self.is_in_check() {
+                -MATE_SCORE + ply
+            } else {
+                0
+            };
+        }
+
+        if depth == 0 {
+            let score = evaluator.evaluate(self);
+
+            return match self.active_color() {
+                Color::White => score,
+                Color::Black => -score,
+            };
+        }
+
+        for mv in moves {
+            let mut board = *self;
+            board.play(mv);
+
+            let score = -board.negamax(depth - 1, ply + 1, -beta, -alpha, evaluator);
+
+            if score >= beta {
+                return beta;
+            }
+
+            alpha = alpha.max(score);
+        }
+
+        alpha
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::*;
+    use crate::{fen::FromFen, square::Square};
+
+    #[test]
+    fn test_material_evaluator() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNB1KBNR w KQkq - 0 1");
+        assert_eq!(-900, MaterialEvaluator.evaluate(&board));
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/4P3/R3K3 b - - 0 1");
+        assert_eq!(600, MaterialEvaluator.evaluate(&board));
+    }
+
+    #[test]
+    fn test_best_move() {
+        let square = |s| Square::try_from(s).unwrap();
+
+        let board = Board::from_fen("4k3/8/8/3q4/8/8/8/3RK3 w - - 0 1");
+        assert_eq!(
+            Some(Move::new(square("d1"), square("d5"))),
+            board.best_move(1, &MaterialEvaluator)
+        );
+
+        // Mate is preferred over any material
+        let board = Board::from_fen("6k1/5ppp/8/8/6r1/4N3/8/R6K w - - 0 1");
+        assert_eq!(
+            Some(Move::new(square("a1"), square("a8"))),
+            board.best_move(2, &MaterialEvaluator)
+        );
+
+        let board = Board::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1");
+        assert_eq!(None, board.best_move(2, &MaterialEvaluator));
+    }
+}
@@ -1,10 +1,11 @@
-use std::convert::TryFrom;
+use std::{convert::TryFrom, fmt::Display};
 
 use crate::{
-    board::{Board, Castling},
+    board::{Board, Castling, MoveResult},
     color::Color,
-    piece::{Piece, PieceType},
-    square::Square,
+    piece::PieceType,
+    rule::Move,
+    square::{Square, BOARD_SIZE},
 };
 
 pub trait FromFen {
@@ -21,43 +22,111 @@ pub trait IntoFen {
     fn into_fen(self) -> String;
 }
 
+/// Error when a FEN string can't be parsed, see [`Board::try_from_fen`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FenError {
+    /// One of the six FEN fields is missing.
+    MissingField,
+    /// The piece placement contains a character that is neither a piece nor a digit, or the
+    /// castling availability one that is not `KQkq`.
+    InvalidPiece(char),
+    /// The side to move is not `w` or `b`.
+    InvalidColor(String),
+    /// The piece placement doesn't fit the board, or the en passant target is not a valid
+    /// target square.
+    InvalidSquare(String),
+    /// A move counter is not a non-negative integer.
+    InvalidNumber,
+}
+
+impl Display for FenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FenError::MissingField => write!(f, "missing FEN field"),
+            FenError::InvalidPiece(c) => write!(f, "invalid piece: {}", c),
+            FenError::InvalidColor(color) => write!(f, "invalid color: {}", color),
+            FenError::InvalidSquare(square) => write!(f, "invalid square: {}", square),
+            FenError::InvalidNumber => write!(f, "invalid move counter"),
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
+
+/// Panics if the FEN is malformed, use [`Board::try_from_fen`] for untrusted input. Unknown
+/// castling characters and an invalid en passant target are ignored.
 impl FromFen for Board {
     fn from_fen(fen: &str) -> Self {
+        Board::parse_fen(fen, false).unwrap_or_else(|err| panic!("Invalid FEN {:?}: {}", fen, err))
+    }
+}
+
+impl Board {
+    /// Parse a position from a FEN string, returning an error instead of panicking if it is
+    /// malformed.
+    pub fn try_from_fen(fen: &str) -> Result<Board, FenError> {
+        Board::parse_fen(fen, true)
+    }
+
+    /// Parse a FEN string, rejecting unknown castling characters and invalid en passant
+    /// targets only if `strict`.
+    fn parse_fen(fen: &str, strict: bool) -> Result<Board, FenError> {
         let mut board = Board::empty();
 
-        let (piece_placement, fen) = fen.split_once(' ').unwrap();
+        let (piece_placement, fen) = fen.split_once(' ').ok_or(FenError::MissingField)?;
 
-        let mut file: i8 = 0;
-        let mut rank: i8 = 7;
+        let ranks = piece_placement.split('/').collect::<Vec<_>>();
+        let invalid_placement = || FenError::InvalidSquare(piece_placement.to_string());
 
-        for c in piece_placement.chars() {
-            if c == '/' {
-                // Jump to the next rank
+        if ranks.len() != BOARD_SIZE as usize {
+            return Err(invalid_placement());
+        }
 
-                rank -= 1;
-                file = 0;
-                continue;
-            }
+        for (rank, pieces) in ranks.iter().enumerate() {
+            let y = BOARD_SIZE - 1 - rank as u8;
+            let mut file: u32 = 0;
+
+            for c in pieces.chars() {
+                if let Some(empty_squares) = c.to_digit(10) {
+                    // Advance file by N empty squares
+
+                    if !(1..=BOARD_SIZE as u32).contains(&empty_squares) {
+                        return Err(invalid_placement());
+                    }
+
+                    file += empty_squares;
+                } else {
+                    let (piece_type, color) =
+                        fen_char_to_piece(c).ok_or(FenError::InvalidPiece(c))?;
+
+                    if file >= BOARD_SIZE as u32 {
+                        return Err(invalid_placement());
+                    }
 
-            if let Some(empty_squares) = c.to_digit(10) {
-                // Advance file by N empty squares
+                    board.set_piece(file as _, y, piece_type, color);
+                    file += 1;
+                }
 
-                file += empty_squares as i8;
-            } else {
-                let (piece_type, color) = fen_char_to_piece(c).unwrap();
+                if file > BOARD_SIZE as u32 {
+                    return Err(invalid_placement());
+                }
+            }
 
-                board.set_piece(file as _, rank as _, piece_type, color);
-                file += 1;
-                //
+            if file != BOARD_SIZE as u32 {
+                return Err(invalid_placement());
             }
         }
 
         let fen = fen.split_whitespace().collect::<Vec<_>>();
 
+        if fen.len() < 5 {
+            return Err(FenError::MissingField);
+        }
+
         *board.active_color_mut() = match fen[0] {
             "b" => Color::Black,
             "w" => Color::White,
-            c => panic!("Invalid color: {}", c),
+            c => return Err(FenError::InvalidColor(c.to_string())),
         };
 
         *board.white_castling_mut() = Castling::none();
@@ -69,18 +138,27 @@ impl FromFen for Board {
                 'Q' => board.white_castling_mut().queenside = true,
                 'k' => board.black_castling_mut().kingside = true,
                 'q' => board.black_castling_mut().queenside = true,
+                '-' if fen[1] == "-" => {}
+                _ if strict => return Err(FenError::InvalidPiece(c)),
                 _ => {}
             }
         }
 
-        if let Ok(en_passant_target) = Square::try_from(fen[2]) {
-            *board.en_passant_target_mut() = Some(en_passant_target);
+        if fen[2] != "-" {
+            let valid = match Square::try_from(fen[2]) {
+                Ok(target) => board.set_en_passant(Some(target)).is_ok(),
+                Err(_) => false,
+            };
+
+            if strict && !valid {
+                return Err(FenError::InvalidSquare(fen[2].to_string()));
+            }
         }
 
-        *board.half_moves_mut() = fen[3].parse::<u32>().unwrap();
-        *board.full_moves_mut() = fen[4].parse::<u32>().unwrap();
+        *board.half_moves_mut() = fen[3].parse::<u32>().map_err(|_| FenError::InvalidNumber)?;
+        *board.full_moves_mut() = fen[4].parse::<u32>().map_err(|_| FenError::InvalidNumber)?;
 
-        board
+        Ok(board)
     }
 }
 
@@ -88,17 +166,17 @@ impl IntoFen for Board {
     fn into_fen(self) -> String {
         let mut fen = String::new();
 
-        for y in (0..8).rev() {
+        for y in (0..BOARD_SIZE).rev() {
             let mut empty_squares = 0;
 
-            for x in 0..8 {
+            for x in 0..BOARD_SIZE {
                 match self.piece(x, y).as_ref() {
                     Some(piece) => {
                         if empty_squares > 0 {
                             fen.push_str(&empty_squares.to_string());
                             empty_squares = 0;
                         }
-                        fen.push_str(&piece_to_fen_char(piece));
+                        fen.push(piece.to_ascii_char());
                     }
                     None => empty_squares += 1,
                 }
@@ -149,6 +227,106 @@ impl IntoFen for Board {
     }
 }
 
+impl Board {
+    /// FEN of the position with White to move. If Black is to move, the FEN of the
+    /// [flipped](Board::flipped) position is returned instead.
+    pub fn to_white_to_move_fen(&self) -> String {
+        match self.active_color() {
+            Color::White => self.into_fen(),
+            Color::Black => self.flipped().into_fen(),
+        }
+    }
+
+    /// FEN of the position where the en passant target is only written if a legal en passant
+    /// capture exists, see [`Board::en_passant_legal_target`].
+    pub fn to_strict_fen(&self) -> String {
+        self.with_legal_en_passant_target().into_fen()
+    }
+
+    /// Key for deduplicating positions, such as transpositions in a database: the first four
+    /// fields of the [strict FEN](Board::to_strict_fen). Positions that differ only by an en
+    /// passant target no pawn can capture on share the key.
+    pub fn canonical_key(&self) -> String {
+        self.with_legal_en_passant_target().to_fen_position()
+    }
+
+    fn with_legal_en_passant_target(&self) -> Board {
+        let mut board = *self;
+        board
+            .set_en_passant(self.en_passant_legal_target())
+            .expect("the en passant target was already valid");
+
+        board
+    }
+
+    /// The first four FEN fields: piece placement, side to move, castling rights and en
+    /// passant target, without the move counters.
+    pub fn to_fen_position(&self) -> String {
+        self.into_fen()
+            .split(' ')
+            .take(4)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// FEN of the position after moving the piece on `from` to `to`, or `None` if the move
+    /// is not legal. The board itself is not changed. A pawn move to the last rank needs a
+    /// promotion piece and is therefore previewed as `None`.
+    pub fn preview_fen(&self, from: impl Into<Square>, to: impl Into<Square>) -> Option<String> {
+        let mut board = *self;
+
+        match board.play(Move::new(from, to)) {
+            MoveResult::Invalid => None,
+            _ => Some(board.into_fen()),
+        }
+    }
+
+    /// The board as a grid of FEN piece letters, `'.'` for empty squares. The first row is
+    /// rank 8 and the first column the a-file, so the grid reads like a diagram from White's
+    /// side: `grid[0][0]` is a8 and `grid[7][7]` is h1.
+    pub fn to_char_grid(&self) -> [[char; 8]; 8] {
+        let mut grid = [['.'; 8]; 8];
+
+        for (from, piece) in self.iter_pieces() {
+            let row = (BOARD_SIZE - 1 - from.y) as usize;
+            grid[row][from.x as usize] = piece.to_ascii_char();
+        }
+
+        grid
+    }
+}
+
+/// Whether the string is a well-formed FEN that [`FromFen::from_fen`] accepts. Only the
+/// syntax is checked, the position itself may be illegal.
+pub fn is_valid_fen(fen: &str) -> bool {
+    Board::try_from_fen(fen).is_ok()
+}
+
+/// Every well-formed FEN found in the text, such as a log file, in the order they appear.
+/// Anything that isn't a FEN is skipped.
+pub fn scan(text: &str) -> Vec<Board> {
+    let mut boards = Vec::new();
+
+    for line in text.lines() {
+        let tokens = line.split_whitespace().collect::<Vec<_>>();
+        let mut i = 0;
+
+        while i + 6 <= tokens.len() {
+            let candidate = tokens[i..i + 6].join(" ");
+
+            match Board::try_from_fen(&candidate) {
+                Ok(board) => {
+                    boards.push(board);
+                    i += 6;
+                }
+                _ => i += 1,
+            }
+        }
+    }
+
+    boards
+}
+
 fn fen_char_to_piece(c: char) -> Option<(PieceType, Color)> {
     let piece_type = match c.to_lowercase().to_string().as_str() {
         "p" => PieceType::Pawn,
@@ -168,27 +346,199 @@ fn fen_char_to_piece(c: char) -> Option<(PieceType, Color)> {
     Some((piece_type, color))
 }
 
-fn piece_to_fen_char(piece: &Piece) -> String {
-    let c = match piece.piece_type() {
-        PieceType::Pawn => "p",
-        PieceType::Knight => "n",
-        PieceType::Bishop => "b",
-        PieceType::Rook => "r",
-        PieceType::Queen => "q",
-        PieceType::King => "k",
-    };
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_fen_ignores_invalid_castling_and_en_passant() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K2R w KQkx e4 0 1");
 
-    match piece.color() {
-        Color::Black => c.to_string(),
-        Color::White => c.to_uppercase(),
+        assert_eq!("4k3/8/8/8/8/8/8/4K2R w KQk - 0 1", board.into_fen());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::board::MoveResult;
+    #[test]
+    fn test_try_from_fen() {
+        assert!(
+            Board::try_from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").is_ok()
+        );
 
-    use super::*;
+        let error = |fen: &str| Board::try_from_fen(fen).err();
+
+        assert_eq!(Some(FenError::MissingField), error("8/8/8/8/8/8/8/8"));
+        assert_eq!(Some(FenError::MissingField), error("8/8/8/8/8/8/8/8 w - -"));
+        assert_eq!(
+            Some(FenError::InvalidPiece('x')),
+            error("4k3/8/8/8/8/8/8/4K2x w - - 0 1")
+        );
+        assert_eq!(
+            Some(FenError::InvalidColor("x".to_string())),
+            error("4k3/8/8/8/8/8/8/4K3 x - - 0 1")
+        );
+        assert_eq!(
+            Some(FenError::InvalidSquare("4k3/8/8/8/8/8/8/4K3R".to_string())),
+            error("4k3/8/8/8/8/8/8/4K3R w - - 0 1")
+        );
+        assert_eq!(
+            Some(FenError::InvalidSquare("99999999999999999999".to_string())),
+            error("99999999999999999999 w - - 0 1")
+        );
+        assert_eq!(
+            Some(FenError::InvalidSquare("/".repeat(200))),
+            error(&format!("{} w - - 0 1", "/".repeat(200)))
+        );
+        assert_eq!(
+            Some(FenError::InvalidSquare("8/8".to_string())),
+            error("8/8 w - - 0 1")
+        );
+        assert_eq!(
+            Some(FenError::InvalidSquare("9/8/8/8/8/8/8/8".to_string())),
+            error("9/8/8/8/8/8/8/8 w - - 0 1")
+        );
+        assert_eq!(
+            Some(FenError::InvalidSquare("4k3/8/8/8/8/8/8/0K7".to_string())),
+            error("4k3/8/8/8/8/8/8/0K7 w - - 0 1")
+        );
+        assert_eq!(
+            Some(FenError::InvalidSquare("4k3/8/8/8/8/8/8/4K2".to_string())),
+            error("4k3/8/8/8/8/8/8/4K2 w - - 0 1")
+        );
+        assert_eq!(
+            Some(FenError::InvalidPiece('x')),
+            error("4k3/8/8/8/8/8/8/4K3 w KQkx - 0 1")
+        );
+        assert_eq!(
+            Some(FenError::InvalidSquare("e9".to_string())),
+            error("4k3/8/8/8/8/8/8/4K3 w - e9 0 1")
+        );
+        assert_eq!(
+            Some(FenError::InvalidSquare("e4".to_string())),
+            error("4k3/8/8/8/8/8/8/4K3 w - e4 0 1")
+        );
+        assert_eq!(
+            Some(FenError::InvalidNumber),
+            error("4k3/8/8/8/8/8/8/4K3 w - - x 1")
+        );
+        assert_eq!(
+            Some(FenError::InvalidNumber),
+            error("4k3/8/8/8/8/8/8/4K3 w - - 0 -1")
+        );
+    }
+
+    #[test]
+    fn test_is_valid_fen() {
+        assert!(is_valid_fen(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+        ));
+        assert!(is_valid_fen("8/8/8/8/8/8/8/8 b - e6 10 42"));
+
+        assert!(!is_valid_fen(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP w KQkq - 0 1"
+        ));
+        assert!(!is_valid_fen(
+            "rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+        ));
+        assert!(!is_valid_fen(
+            "rnbqkbnr/pppxpppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+        ));
+        assert!(!is_valid_fen(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1"
+        ));
+        assert!(!is_valid_fen(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkx - 0 1"
+        ));
+        assert!(!is_valid_fen(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq e4 0 1"
+        ));
+        assert!(!is_valid_fen(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0"
+        ));
+    }
+
+    #[test]
+    fn test_scan() {
+        let text = "\
+[info] new game
+[info] position rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 loaded
+not a fen: rnbqkbnr/pppppppp/8 w - - 0 1
+8/8/4k3/8/4P3/4K3/8/8 b - - 3 40
+";
+
+        let boards = scan(text);
+
+        assert_eq!(2, boards.len());
+        assert_eq!(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            boards[0].into_fen()
+        );
+        assert_eq!("8/8/4k3/8/4P3/4K3/8/8 b - - 3 40", boards[1].into_fen());
+    }
+
+    #[test]
+    fn test_to_strict_fen() {
+        let mut board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        board.play_move((4, 1), (4, 3));
+
+        assert_eq!(
+            Some(Square::try_from("e3").unwrap()),
+            board.en_passant_target()
+        );
+        assert_eq!(None, board.en_passant_legal_target());
+        assert_eq!(
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1",
+            board.to_strict_fen()
+        );
+
+        let board = Board::from_fen("rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 3");
+        assert_eq!(
+            Some(Square::try_from("e3").unwrap()),
+            board.en_passant_legal_target()
+        );
+        assert_eq!(board.into_fen(), board.to_strict_fen());
+    }
+
+    #[test]
+    fn test_to_fen_position() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert_eq!(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -",
+            board.to_fen_position()
+        );
+
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+        assert_eq!(
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3",
+            board.to_fen_position()
+        );
+    }
+
+    #[test]
+    fn test_preview_fen() {
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        let board = Board::from_fen(fen);
+
+        assert_eq!(
+            Some("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1".to_string()),
+            board.preview_fen((4, 1), (4, 3))
+        );
+        assert_eq!(None, board.preview_fen((4, 1), (4, 4)));
+        assert_eq!(fen, board.into_fen());
+    }
+
+    #[test]
+    fn test_to_white_to_move_fen() {
+        let fen = "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b Kq e3 0 1";
+        let board = Board::from_fen(fen);
+
+        assert_eq!(
+            "rnbqkbnr/pppp1ppp/8/4p3/8/8/PPPPPPPP/RNBQKBNR w Qk e6 0 1",
+            board.to_white_to_move_fen()
+        );
+        assert_eq!(fen, board.flipped().flipped().into_fen());
+
+        let fen = "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2";
+        assert_eq!(fen, Board::from_fen(fen).to_white_to_move_fen());
+    }
 
     #[test]
     fn test_board_into_fen() {
@@ -302,4 +652,35 @@ mod tests {
             board.into_fen()
         );
     }
+
+    #[test]
+    fn test_canonical_key() {
+        let with_target =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+        let without_target =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 5 9");
+
+        assert_eq!(with_target.canonical_key(), without_target.canonical_key());
+        assert_eq!(
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq -",
+            with_target.canonical_key()
+        );
+
+        let capturable = Board::from_fen("4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 1");
+        assert_eq!("4k3/8/8/8/3pP3/8/8/4K3 b - e3", capturable.canonical_key());
+    }
+
+    #[test]
+    fn test_to_char_grid() {
+        let grid = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")
+            .to_char_grid();
+
+        assert_eq!('r', grid[0][0]);
+        assert_eq!('r', grid[0][7]);
+        assert_eq!('R', grid[7][0]);
+        assert_eq!('R', grid[7][7]);
+        assert_eq!('k', grid[0][4]);
+        assert_eq!('K', grid[7][4]);
+        assert_eq!(['.'; 8], grid[4]);
+    }
 }
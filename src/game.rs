@@ -0,0 +1,398 @@
+use crate::{
+    board::{Board, MoveResult},
+    color::Color,
+    fen::IntoFen,
+    rule::Move,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameResult {
+    Ongoing,
+    WhiteWins,
+    BlackWins,
+    Draw,
+}
+
+impl GameResult {
+    /// The result as written at the end of PGN movetext.
+    pub fn pgn_token(&self) -> &'static str {
+        match self {
+            GameResult::Ongoing => "*",
+            GameResult::WhiteWins => "1-0",
+            GameResult::BlackWins => "0-1",
+            GameResult::Draw => "1/2-1/2",
+        }
+    }
+}
+
+impl Board {
+    /// The result decided on the board: a win for the side not to move on checkmate, a draw
+    /// on stalemate, insufficient material or after 75 moves by each side without a capture
+    /// or pawn move, and ongoing otherwise. Checkmate on the last of those moves still wins.
+    pub fn game_result(&self) -> GameResult {
+        if !self.all_legal_moves().is_empty() {
+            if self.half_moves() >= 150 || self.is_insufficient_material() {
+                GameResult::Draw
+            } else {
+                GameResult::Ongoing
+            }
+        } else if !self.is_in_check() {
+            GameResult::Draw
+        } else if self.active_color() == Color::White {
+            GameResult::BlackWins
+        } else {
+            GameResult::WhiteWins
+        }
+    }
+
+    /// The result as written in PGN: `1-0`, `0-1`, `1/2-1/2` or `*`. A result set with
+    /// [`Board::set_result`] takes precedence over the one decided on the board.
+    pub fn result_string(&self) -> &'static str {
+        self.result()
+            .unwrap_or_else(|| self.game_result())
+            .pgn_token()
+    }
+}
+
+/// A game played from a starting position, keeping the moves played. Results that are not
+/// decided on the board, such as resignation, are set on the current board with
+/// [`Board::set_result`].
+#[derive(Debug, Clone)]
+pub struct Game {
+    start: Board,
+    board: Board,
+    moves: Vec<Move>,
+    hashes: Vec<u64>,
+}
+
+impl Game {
+    pub fn new(start: Board) -> Self {
+        Self {
+            start,
+            board: start,
+            moves: Vec::new(),
+            hashes: vec![start.repetition_hash()],
+        }
+    }
+
+    pub fn start_board(&self) -> &Board {
+        &self.start
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    /// The position after the first `ply` half-moves of the game, or `None` if fewer moves
+    /// have been played. Ply 0 is the starting position.
+    pub fn board_at_ply(&self, ply: usize) -> Option<Board> {
+        let moves = self.moves.get(..ply)?;
+        let mut board = self.start;
+
+        for mv in moves {
+            board.play(*mv);
+        }
+
+        Some(board)
+    }
+
+    /// FEN of the position after the first `ply` half-moves, see [`Game::board_at_ply`].
+    pub fn fen_at_ply(&self, ply: usize) -> Option<String> {
+        self.board_at_ply(ply).map(|board| board.into_fen())
+    }
+
+    /// Play a legal move. Moves are rejected once the game has a result.
+    pub fn play(&mut self, mv: Move) -> MoveResult {
+        if self.result() != GameResult::Ongoing {
+            return MoveResult::Invalid;
+        }
+
+        let result = self.board.play(mv);
+
+        if result != MoveResult::Invalid {
+            self.moves.push(mv);
+            self.hashes.push(self.board.repetition_hash());
+        }
+
+        result
+    }
+
+    /// End the game with the given color resigning.
+    pub fn resign(&mut self, color: Color) {
+        self.board.set_result(match color {
+            Color::White => GameResult::BlackWins,
+            Color::Black => GameResult::WhiteWins,
+        });
+    }
+
+    /// End the game in a draw agreed by both players.
+    pub fn agree_draw(&mut self) {
+        self.board.set_result(GameResult::Draw);
+    }
+
+    /// The result of the game: set by resignation or draw agreement, drawn by fivefold
+    /// repetition, or decided on the board (see [`Board::game_result`]).
+    pub fn result(&self) -> GameResult {
+        self.board.result().unwrap_or_else(|| {
+            if self.is_fivefold_repetition() {
+                GameResult::Draw
+            } else {
+                self.board.game_result()
+            }
+        })
+    }
+
+    /// Number of times the current position has occurred in the game, counting the current
+    /// occurrence. Positions are compared by [`Board::repetition_hash`].
+    pub fn repetition_count(&self) -> usize {
+        let current = self.board.repetition_hash();
+
+        self.hashes.iter().filter(|&&hash| hash == current).count()
+    }
+
+    /// Whether the current position has occurred at least three times, so that a player may
+    /// claim a draw by threefold repetition. The game goes on unless a draw is claimed.
+    pub fn can_claim_threefold(&self) -> bool {
+        self.repetition_count() >= 3
+    }
+
+    /// Whether the current position has occurred at least five times, which draws the game
+    /// without a claim.
+    pub fn is_fivefold_repetition(&self) -> bool {
+        self.repetition_count() >= 5
+    }
+
+    /// The moves in SAN paired by move number as `(number, white, black)`, for a two-column
+    /// move list. The first entry has no White move if Black moved first, and the last has
+    /// no Black move if Black has not replied yet.
+    pub fn move_list(&self) -> Vec<(u32, Option<String>, Option<String>)> {
+        let mut board = self.start;
+        let mut list: Vec<(u32, Option<String>, Option<String>)> = Vec::new();
+
+        for mv in &self.moves {
+            let san = board.move_to_san(mv);
+
+            match board.active_color() {
+                Color::White => list.push((board.full_moves(), san, None)),
+                Color::Black => match list.last_mut() {
+                    Some(last) if last.0 == board.full_moves() => last.2 = san,
+                    _ => list.push((board.full_moves(), None, san)),
+                },
+            }
+
+            board.play(*mv);
+        }
+
+        list
+    }
+
+    /// PGN movetext of the game in SAN, ending with the result token.
+    pub fn pgn(&self) -> String {
+        let mut board = self.start;
+        let mut tokens = Vec::new();
+
+        for (i, mv) in self.moves.iter().enumerate() {
+            if board.active_color() == Color::White {
+                tokens.push(format!("{}.", board.full_moves()));
+            } else if i == 0 {
+                tokens.push(format!("{}...", board.full_moves()));
+            }
+
+            tokens.push(board.move_to_san(mv).unwrap_or_default());
+            board.play(*mv);
+        }
+
+        tokens.push(self.result().pgn_token().to_string());
+        tokens.join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fen::FromFen;
+
+    fn start() -> Board {
+        Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")
+    }
+
+    #[test]
+    fn test_resign() {
+        let mut game = Game::new(start());
+        let e4 = game.board().parse_san("e4").unwrap();
+        assert_eq!(MoveResult::Ok, game.play(e4));
+
+        game.resign(Color::White);
+
+        assert_eq!(GameResult::BlackWins, game.result());
+        assert_eq!("0-1", game.result().pgn_token());
+        assert_eq!("1. e4 0-1", game.pgn());
+        assert_eq!("0-1", game.board().result_string());
+
+        let e5 = game.board().parse_san("e5").unwrap();
+        assert_eq!(MoveResult::Invalid, game.play(e5));
+    }
+
+    #[test]
+    fn test_agree_draw() {
+        let mut game = Game::new(start());
+        assert_eq!(GameResult::Ongoing, game.result());
+        assert_eq!("*", game.pgn());
+
+        game.agree_draw();
+
+        assert_eq!(GameResult::Draw, game.result());
+        assert_eq!("1/2-1/2", game.pgn());
+        assert_eq!("1/2-1/2", game.board().result_string());
+    }
+
+    #[test]
+    fn test_checkmate_result() {
+        let mut game = Game::new(start());
+
+        for san in &["f3", "e5", "g4", "Qh4#"] {
+            let mv = game.board().parse_san(san).unwrap();
+            assert_eq!(MoveResult::Ok, game.play(mv));
+        }
+
+        assert_eq!(GameResult::BlackWins, game.result());
+        assert_eq!("1. f3 e5 2. g4 Qh4# 0-1", game.pgn());
+    }
+
+    #[test]
+    fn test_board_result_string() {
+        assert_eq!("*", start().result_string());
+
+        let board =
+            Board::from_fen("rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3");
+        assert_eq!(GameResult::BlackWins, board.game_result());
+        assert_eq!("0-1", board.result_string());
+
+        let board = Board::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 1 1");
+        assert_eq!("1-0", board.result_string());
+
+        let board = Board::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1");
+        assert_eq!("1/2-1/2", board.result_string());
+    }
+
+    #[test]
+    fn test_board_result_draw_rules() {
+        let board = Board::from_fen("8/8/4k3/8/8/4KB2/8/8 b - - 0 60");
+        assert_eq!(GameResult::Draw, board.game_result());
+
+        let board = Board::from_fen("8/8/4k3/8/8/4KR2/8/8 b - - 149 90");
+        assert_eq!(GameResult::Ongoing, board.game_result());
+
+        let board = Board::from_fen("8/8/4k3/8/8/4KR2/8/8 b - - 150 90");
+        assert_eq!(GameResult::Draw, board.game_result());
+
+        let board = Board::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 150 90");
+        assert_eq!(GameResult::WhiteWins, board.game_result());
+    }
+
+    #[test]
+    fn test_board_imposed_result() {
+        let mut board = start();
+        assert_eq!(None, board.result());
+
+        board.set_result(GameResult::BlackWins);
+        assert_eq!(Some(GameResult::BlackWins), board.result());
+        assert_eq!(GameResult::Ongoing, board.game_result());
+        assert_eq!("0-1", board.result_string());
+        assert_eq!(Some(GameResult::WhiteWins), board.flipped().result());
+    }
+
+    #[test]
+    fn test_fen_at_ply() {
+        let mut game = Game::new(start());
+
+        for san in &["e4", "e5", "Nf3", "Nc6"] {
+            let mv = game.board().parse_san(san).unwrap();
+            game.play(mv);
+        }
+
+        assert_eq!(
+            Some("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1".to_string()),
+            game.fen_at_ply(0)
+        );
+        assert_eq!(
+            Some("rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2".to_string()),
+            game.fen_at_ply(3)
+        );
+        assert_eq!(Some(game.board().into_fen()), game.fen_at_ply(4));
+        assert_eq!(None, game.fen_at_ply(5));
+    }
+
+    #[test]
+    fn test_move_list() {
+        let mut game = Game::new(start());
+
+        for san in &["e4", "e5", "Nf3", "Nc6", "Bb5"] {
+            let mv = game.board().parse_san(san).unwrap();
+            game.play(mv);
+        }
+
+        let san = |s: &str| Some(s.to_string());
+
+        assert_eq!(
+            vec![
+                (1, san("e4"), san("e5")),
+                (2, san("Nf3"), san("Nc6")),
+                (3, san("Bb5"), None),
+            ],
+            game.move_list()
+        );
+
+        let mut game = Game::new(Board::from_fen(
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+        ));
+        let mv = game.board().parse_san("c5").unwrap();
+        game.play(mv);
+
+        assert_eq!(vec![(1, None, san("c5"))], game.move_list());
+    }
+
+    #[test]
+    fn test_repetition() {
+        let mut game = Game::new(start());
+
+        let shuffle = ["Nf3", "Nf6", "Ng1", "Ng8"];
+
+        assert_eq!(1, game.repetition_count());
+
+        for occurrence in 2..=5 {
+            for san in &shuffle {
+                let mv = game.board().parse_san(san).unwrap();
+                assert_eq!(MoveResult::Ok, game.play(mv));
+            }
+
+            assert_eq!(occurrence, game.repetition_count());
+            assert_eq!(occurrence >= 3, game.can_claim_threefold());
+            assert_eq!(occurrence >= 5, game.is_fivefold_repetition());
+        }
+
+        assert_eq!(GameResult::Draw, game.result());
+
+        let e4 = game.board().parse_san("e4").unwrap();
+        assert_eq!(MoveResult::Invalid, game.play(e4));
+    }
+
+    #[test]
+    fn test_repetition_after_double_push() {
+        let mut game = Game::new(start());
+
+        for san in &["e4", "Nf6", "Nf3", "Ng8", "Ng1"] {
+            let mv = game.board().parse_san(san).unwrap();
+            assert_eq!(MoveResult::Ok, game.play(mv));
+        }
+
+        // The en passant target left by e4 can't be captured on, so the position after Ng1
+        // repeats the one after e4.
+        assert_eq!(None, game.board().en_passant_target());
+        assert_eq!(2, game.repetition_count());
+    }
+}
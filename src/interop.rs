@@ -0,0 +1,57 @@
+//! Conversions to and from [`shakmaty`] positions, using FEN as the bridge.
+
+use std::convert::TryFrom;
+
+use shakmaty::{fen::Fen, CastlingMode, Chess, EnPassantMode, PositionError};
+
+use crate::{
+    board::Board,
+    fen::{FromFen, IntoFen},
+};
+
+impl From<&Chess> for Board {
+    fn from(position: &Chess) -> Self {
+        let fen = Fen::from_position(position, EnPassantMode::Legal);
+        Board::from_fen(&fen.to_string())
+    }
+}
+
+impl TryFrom<Board> for Chess {
+    type Error = PositionError<Chess>;
+
+    /// Fails if the board is not a legal shakmaty position, for example when a side has
+    /// no king or the side not to move is in check.
+    fn try_from(board: Board) -> Result<Self, Self::Error> {
+        let fen: Fen = board
+            .into_fen()
+            .parse()
+            .expect("board FEN should always be well-formed");
+
+        fen.into_position(CastlingMode::Standard)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shakmaty::Position;
+
+    #[test]
+    fn test_round_trip() {
+        let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+        let board = Board::from_fen(fen);
+
+        let chess = Chess::try_from(board).unwrap();
+        assert_eq!(
+            fen,
+            Fen::from_position(&chess, EnPassantMode::Legal).to_string()
+        );
+        assert_eq!(fen, Board::from(&chess).into_fen());
+        assert_eq!(
+            chess.legal_moves().len(),
+            Board::from(&chess).all_legal_moves().len()
+        );
+
+        assert!(Chess::try_from(Board::empty()).is_err());
+    }
+}
@@ -1,7 +1,21 @@
+pub mod attack_map;
 #[deny(clippy::all)]
 pub mod board;
+pub mod book;
+pub mod cache;
 pub mod color;
+pub mod encoding;
+pub mod eval;
 pub mod fen;
+pub mod game;
+#[cfg(feature = "shakmaty")]
+pub mod interop;
+pub mod material;
+pub mod pawn_structure;
+pub mod perft;
 pub mod piece;
 pub mod rule;
+pub mod san;
 pub mod square;
+pub mod tactics;
+pub mod zobrist;
@@ -0,0 +1,287 @@
+use crate::{board::Board, color::Color, piece::PieceType};
+
+/// Phase score of the starting position, see [`Board::phase_score`].
+pub const MAX_PHASE_SCORE: u32 = 24;
+
+/// Positions with at least this phase score are classified as [`GamePhase::Opening`].
+pub const OPENING_PHASE_SCORE: u32 = 22;
+
+/// Positions with at most this phase score are classified as [`GamePhase::Endgame`].
+pub const ENDGAME_PHASE_SCORE: u32 = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GamePhase {
+    Opening,
+    Middlegame,
+    Endgame,
+}
+
+/// Number of pieces of each type a side has, not counting the king.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Material {
+    pub pawns: u8,
+    pub knights: u8,
+    pub bishops: u8,
+    pub rooks: u8,
+    pub queens: u8,
+}
+
+impl Material {
+    /// Total value of the material in centipawns.
+    pub fn value(&self) -> u32 {
+        self.pawns as u32 * PieceType::Pawn.value()
+            + self.knights as u32 * PieceType::Knight.value()
+            + self.bishops as u32 * PieceType::Bishop.value()
+            + self.rooks as u32 * PieceType::Rook.value()
+            + self.queens as u32 * PieceType::Queen.value()
+    }
+}
+
+impl Board {
+    pub fn material(&self, color: Color) -> Material {
+        let mut material = Material::default();
+
+        for (_, piece) in self.pieces_of(color) {
+            match piece.piece_type() {
+                PieceType::Pawn => material.pawns += 1,
+                PieceType::Knight => material.knights += 1,
+                PieceType::Bishop => material.bishops += 1,
+                PieceType::Rook => material.rooks += 1,
+                PieceType::Queen => material.queens += 1,
+                PieceType::King => {}
+            }
+        }
+
+        material
+    }
+
+    /// Number of queens of the given color, including promoted ones.
+    pub fn queen_count(&self, color: Color) -> u8 {
+        self.material(color).queens
+    }
+
+    /// Whether the material of the given color could arise in a game: every piece beyond the
+    /// starting set must be a promoted pawn, so pawns and extra pieces add up to at most 8.
+    /// Strict importers use this as a sanity limit, the move history is not verified.
+    pub fn has_plausible_material(&self, color: Color) -> bool {
+        let material = self.material(color);
+
+        let promoted = material.queens.saturating_sub(1)
+            + material.rooks.saturating_sub(2)
+            + material.bishops.saturating_sub(2)
+            + material.knights.saturating_sub(2);
+
+        material.pawns + promoted <= 8
+    }
+
+    /// Material value of White minus that of Black, in centipawns.
+    pub fn material_balance(&self) -> i32 {
+        self.material(Color::White).value() as i32 - self.material(Color::Black).value() as i32
+    }
+
+    /// Phase score from the non-pawn material of both sides. Knights and bishops count 1,
+    /// rooks 2 and queens 4, capped at [`MAX_PHASE_SCORE`] for positions with promoted pieces.
+    pub fn phase_score(&self) -> u32 {
+        let score = [Color::White, Color::Black]
+            .iter()
+            .map(|color| {
+                let material = self.material(*color);
+
+                material.knights as u32
+                    + material.bishops as u32
+                    + material.rooks as u32 * 2
+                    + material.queens as u32 * 4
+            })
+            .sum::<u32>();
+
+        score.min(MAX_PHASE_SCORE)
+    }
+
+    /// Classify the game phase from the remaining material using the phase score thresholds.
+    pub fn game_phase(&self) -> GamePhase {
+        let score = self.phase_score();
+
+        if score >= OPENING_PHASE_SCORE {
+            GamePhase::Opening
+        } else if score <= ENDGAME_PHASE_SCORE {
+            GamePhase::Endgame
+        } else {
+            GamePhase::Middlegame
+        }
+    }
+
+    /// Whether only kings and pawns are left on the board.
+    pub fn is_pawn_endgame(&self) -> bool {
+        self.pieces()
+            .iter()
+            .all(|p| matches!(p.piece_type(), PieceType::King | PieceType::Pawn))
+    }
+
+    /// Number of king moves between the two kings, or 0 if either king is missing.
+    pub fn king_distance(&self) -> u8 {
+        match (
+            self.find_all(PieceType::King, Color::White).first(),
+            self.find_all(PieceType::King, Color::Black).first(),
+        ) {
+            (Some(white), Some(black)) => white.chebyshev_distance(*black),
+            _ => 0,
+        }
+    }
+
+    /// Whether the given color has enough material to mate the opposing king without the
+    /// help of the opponent's pieces. A lone king, a single minor piece or bishops all on
+    /// squares of one color can't mate.
+    ///
+    /// Used to decide a timeout, which is a draw when the side on time can't deliver mate.
+    pub fn has_mating_material(&self, color: Color) -> bool {
+        let material = self.material(color);
+
+        if material.pawns > 0 || material.rooks > 0 || material.queens > 0 {
+            return true;
+        }
+
+        if material.knights > 0 {
+            return material.knights + material.bishops >= 2;
+        }
+
+        let bishops = self.find_all(PieceType::Bishop, color);
+        bishops
+            .iter()
+            .any(|b| (b.x + b.y) % 2 != (bishops[0].x + bishops[0].y) % 2)
+    }
+
+    /// Whether neither side can mate with any sequence of legal moves because too little
+    /// material is left: kings with at most one minor piece, or with only bishops that all
+    /// stand on squares of one color.
+    pub fn is_insufficient_material(&self) -> bool {
+        if self.has_mating_material(Color::White) || self.has_mating_material(Color::Black) {
+            return false;
+        }
+
+        let white = self.material(Color::White);
+        let black = self.material(Color::Black);
+
+        if white.knights + white.bishops + black.knights + black.bishops <= 1 {
+            return true;
+        }
+
+        let mut bishops = self.find_all(PieceType::Bishop, Color::White);
+        bishops.extend(self.find_all(PieceType::Bishop, Color::Black));
+
+        white.knights + black.knights == 0
+            && bishops
+                .iter()
+                .all(|b| (b.x + b.y) % 2 == (bishops[0].x + bishops[0].y) % 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fen::FromFen;
+
+    #[test]
+    fn test_start_position_material() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+        let start = Material {
+            pawns: 8,
+            knights: 2,
+            bishops: 2,
+            rooks: 2,
+            queens: 1,
+        };
+
+        assert_eq!(start, board.material(Color::White));
+        assert_eq!(start, board.material(Color::Black));
+        assert_eq!(4_000, start.value());
+        assert_eq!(0, board.material_balance());
+
+        let board = Board::from_fen("rnb1kbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert_eq!(900, board.material_balance());
+    }
+
+    #[test]
+    fn test_game_phase() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert_eq!(MAX_PHASE_SCORE, board.phase_score());
+        assert_eq!(GamePhase::Opening, board.game_phase());
+        assert!(!board.is_pawn_endgame());
+
+        let board = Board::from_fen("r3k2r/pp3ppp/2n5/8/8/2N5/PP3PPP/R3K2R w KQkq - 0 1");
+        assert_eq!(GamePhase::Middlegame, board.game_phase());
+
+        let board = Board::from_fen("8/8/4k3/8/4P3/4K3/8/8 w - - 0 1");
+        assert_eq!(0, board.phase_score());
+        assert_eq!(GamePhase::Endgame, board.game_phase());
+        assert!(board.is_pawn_endgame());
+    }
+
+    #[test]
+    fn test_king_distance() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert_eq!(7, board.king_distance());
+
+        let board = Board::from_fen("8/8/8/3k4/4K3/8/8/8 w - - 0 1");
+        assert_eq!(1, board.king_distance());
+
+        assert_eq!(0, Board::empty().king_distance());
+    }
+
+    #[test]
+    fn test_has_mating_material() {
+        let board = Board::from_fen("8/8/4k3/8/8/4KN2/8/8 w - - 0 1");
+        assert!(!board.has_mating_material(Color::White));
+        assert!(!board.has_mating_material(Color::Black));
+
+        let board = Board::from_fen("8/8/4k3/8/8/4KR2/8/8 w - - 0 1");
+        assert!(board.has_mating_material(Color::White));
+        assert!(!board.has_mating_material(Color::Black));
+
+        let board = Board::from_fen("8/8/4k3/8/8/2B1KB2/8/8 w - - 0 1");
+        assert!(board.has_mating_material(Color::White));
+
+        let board = Board::from_fen("8/8/4k3/8/8/3BKB2/8/8 w - - 0 1");
+        assert!(!board.has_mating_material(Color::White));
+
+        let board = Board::from_fen("8/8/4k3/8/8/4KBN1/8/8 w - - 0 1");
+        assert!(board.has_mating_material(Color::White));
+    }
+
+    #[test]
+    fn test_is_insufficient_material() {
+        assert!(Board::from_fen("8/8/4k3/8/8/4K3/8/8 w - - 0 1").is_insufficient_material());
+        assert!(Board::from_fen("8/8/4k3/8/8/4KN2/8/8 w - - 0 1").is_insufficient_material());
+
+        // Bishops on squares of one color, whichever side they belong to
+        let board = Board::from_fen("8/8/4kb2/8/8/2B1K3/8/8 w - - 0 1");
+        assert!(board.is_insufficient_material());
+
+        // Mate is possible with help from the opponent
+        let board = Board::from_fen("8/8/4kb2/8/8/3BK3/8/8 w - - 0 1");
+        assert!(!board.is_insufficient_material());
+        let board = Board::from_fen("8/8/4kn2/8/8/4KN2/8/8 w - - 0 1");
+        assert!(!board.is_insufficient_material());
+
+        let board = Board::from_fen("8/8/4k3/8/8/4KR2/8/8 w - - 0 1");
+        assert!(!board.is_insufficient_material());
+    }
+
+    #[test]
+    fn test_has_plausible_material() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert!(board.has_plausible_material(Color::White));
+        assert!(board.has_plausible_material(Color::Black));
+        assert!(board.is_legal_position());
+
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/2QQ4/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        assert_eq!(3, board.queen_count(Color::White));
+        assert!(!board.has_plausible_material(Color::White));
+        assert!(board.has_plausible_material(Color::Black));
+        assert!(!board.is_legal_position());
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/QQQQQQ2/QQQ1K3 w - - 0 1");
+        assert_eq!(9, board.queen_count(Color::White));
+        assert!(board.has_plausible_material(Color::White));
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{
+    board::Board,
+    color::Color,
+    piece::PieceType,
+    square::{Square, BOARD_SIZE},
+};
+
+impl Board {
+    /// Pawns of the given color that share their file with another pawn of the same color.
+    pub fn doubled_pawns(&self, color: Color) -> Vec<Square> {
+        let pawns = self.find_all(PieceType::Pawn, color);
+
+        pawns
+            .iter()
+            .filter(|pawn| pawns.iter().filter(|other| other.x == pawn.x).count() > 1)
+            .copied()
+            .collect()
+    }
+
+    /// Pawns of the given color with no pawn of the same color on an adjacent file.
+    pub fn isolated_pawns(&self, color: Color) -> Vec<Square> {
+        let pawns = self.find_all(PieceType::Pawn, color);
+
+        pawns
+            .iter()
+            .filter(|pawn| !pawns.iter().any(|other| is_adjacent_file(pawn, other)))
+            .copied()
+            .collect()
+    }
+
+    /// Pawns of the given color with no enemy pawn in front of them on the same or an
+    /// adjacent file.
+    pub fn passed_pawns(&self, color: Color) -> Vec<Square> {
+        let enemy_pawns = self.find_all(PieceType::Pawn, color.opposite());
+
+        self.find_all(PieceType::Pawn, color)
+            .into_iter()
+            .filter(|pawn| {
+                !enemy_pawns.iter().any(|enemy| {
+                    let in_front = match color {
+                        Color::White => enemy.y > pawn.y,
+                        Color::Black => enemy.y < pawn.y,
+                    };
+
+                    in_front && (enemy.x == pawn.x || is_adjacent_file(pawn, enemy))
+                })
+            })
+            .collect()
+    }
+
+    /// Whether the file has no pawns of either color.
+    pub fn is_open_file(&self, x: u8) -> bool {
+        !self.file(x).any(|(_, piece)| {
+            piece
+                .filter(|p| p.piece_type() == PieceType::Pawn)
+                .is_some()
+        })
+    }
+
+    /// Whether the file has no pawns of the given color.
+    pub fn is_semi_open_file(&self, x: u8, color: Color) -> bool {
+        !self.file(x).any(|(_, piece)| {
+            piece
+                .filter(|p| p.piece_type() == PieceType::Pawn && p.color() == color)
+                .is_some()
+        })
+    }
+
+    /// Number of ranks the pawn on the square must advance to promote, or `None` if there is
+    /// no pawn on the square.
+    pub fn squares_to_promotion(&self, square: impl Into<Square>) -> Option<u8> {
+        let square = square.into();
+
+        self.piece_at(square)
+            .filter(|p| p.piece_type() == PieceType::Pawn)
+            .map(|pawn| match pawn.color() {
+                Color::White => BOARD_SIZE - 1 - square.y,
+                Color::Black => square.y,
+            })
+    }
+}
+
+fn is_adjacent_file(a: &Square, b: &Square) -> bool {
+    (a.x as i8 - b.x as i8).abs() == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::*;
+    use crate::fen::FromFen;
+
+    fn squares(names: &[&str]) -> Vec<Square> {
+        let mut squares = names
+            .iter()
+            .map(|name| Square::try_from(*name).unwrap())
+            .collect::<Vec<_>>();
+        squares.sort_by_key(|square| (square.y, square.x));
+        squares
+    }
+
+    #[test]
+    fn test_doubled_and_isolated_pawns() {
+        let board = Board::from_fen("4k3/pp3ppp/8/8/8/2P5/2P2PPP/4K3 w - - 0 1");
+
+        assert_eq!(squares(&["c2", "c3"]), board.doubled_pawns(Color::White));
+        assert_eq!(squares(&["c2", "c3"]), board.isolated_pawns(Color::White));
+        assert!(board.doubled_pawns(Color::Black).is_empty());
+        assert!(board.isolated_pawns(Color::Black).is_empty());
+    }
+
+    #[test]
+    fn test_passed_pawns() {
+        let board = Board::from_fen("4k3/p5pp/8/3P4/8/8/6PP/4K3 w - - 0 1");
+
+        assert_eq!(squares(&["d5"]), board.passed_pawns(Color::White));
+        assert_eq!(squares(&["a7"]), board.passed_pawns(Color::Black));
+    }
+
+    #[test]
+    fn test_open_files() {
+        let board = Board::from_fen("3rk3/ppp2ppp/4p3/8/8/2P5/PP3PPP/3RK3 w - - 0 1");
+
+        assert!(board.is_open_file(3));
+        assert!(board.is_semi_open_file(3, Color::White));
+        assert!(board.is_semi_open_file(3, Color::Black));
+
+        assert!(!board.is_open_file(4));
+        assert!(board.is_semi_open_file(4, Color::White));
+        assert!(!board.is_semi_open_file(4, Color::Black));
+
+        assert!(!board.is_open_file(0));
+        assert!(!board.is_semi_open_file(0, Color::White));
+    }
+
+    #[test]
+    fn test_squares_to_promotion() {
+        let board = Board::from_fen("4k3/4p3/8/8/8/8/4P3/4K3 w - - 0 1");
+
+        assert_eq!(
+            Some(6),
+            board.squares_to_promotion(Square::try_from("e2").unwrap())
+        );
+        assert_eq!(
+            Some(6),
+            board.squares_to_promotion(Square::try_from("e7").unwrap())
+        );
+        assert_eq!(
+            None,
+            board.squares_to_promotion(Square::try_from("e1").unwrap())
+        );
+        assert_eq!(
+            None,
+            board.squares_to_promotion(Square::try_from("e4").unwrap())
+        );
+    }
+}
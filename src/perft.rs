@@ -0,0 +1,192 @@
+use crate::{
+    board::Board,
+    rule::{Move, MoveType},
+};
+
+/// Leaf counts of a [perft](Board::perft_with_stats) run broken down by move category, in
+/// the layout of the published perft tables. The categories overlap: an en passant capture
+/// is also a capture and a checkmate is also a check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PerftStats {
+    pub nodes: u64,
+    pub captures: u64,
+    pub en_passants: u64,
+    pub castles: u64,
+    pub promotions: u64,
+    pub checks: u64,
+    pub checkmates: u64,
+}
+
+impl PerftStats {
+    fn add(&mut self, other: &PerftStats) {
+        self.nodes += other.nodes;
+        self.captures += other.captures;
+        self.en_passants += other.en_passants;
+        self.castles += other.castles;
+        self.promotions += other.promotions;
+        self.checks += other.checks;
+        self.checkmates += other.checkmates;
+    }
+}
+
+impl Board {
+    /// Number of leaf positions reached by playing every legal move sequence of the given
+    /// length. Comparing against known counts is the standard check of move generation.
+    pub fn perft(&self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let moves = self.all_legal_moves();
+
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+
+        moves
+            .into_iter()
+            .map(|mv| {
+                let mut board = *self;
+                board.play(mv);
+                board.perft(depth - 1)
+            })
+            .sum()
+    }
+
+    /// [Perft](Board::perft) with the moves leading to the leaf positions counted by category.
+    /// Slower than counting the nodes alone, so meant for pinning down which kind of move is
+    /// miscounted.
+    pub fn perft_with_stats(&self, depth: u32) -> PerftStats {
+        let mut stats = PerftStats::default();
+
+        if depth == 0 {
+            stats.nodes = 1;
+            return stats;
+        }
+
+        let moves = self.all_legal_moves();
+
+        if depth > 1 {
+            for mv in moves {
+                let mut board = *self;
+                board.play(mv);
+                stats.add(&board.perft_with_stats(depth - 1));
+            }
+
+            return stats;
+        }
+
+        let en_passants = self.legal_moves_of_type(MoveType::EnPassant);
+        let castles = self.legal_moves_of_type(MoveType::Castling);
+
+        for mv in moves {
+            let mut board = *self;
+            board.play(mv);
+
+            stats.nodes += 1;
+            stats.captures += self.is_capture(&mv) as u64;
+            stats.en_passants += en_passants.contains(&mv) as u64;
+            stats.castles += castles.contains(&mv) as u64;
+            stats.promotions += mv.promotion.is_some() as u64;
+            stats.checks += board.is_in_check() as u64;
+            stats.checkmates += board.is_checkmate() as u64;
+        }
+
+        stats
+    }
+
+    /// Every legal move with the [perft](Board::perft) count of the position after it,
+    /// sorted by the moves' UCI notation to match the output of other engines.
+    pub fn perft_divide(&self, depth: u32) -> Vec<(Move, u64)> {
+        if depth == 0 {
+            return Vec::new();
+        }
+
+        let mut divide = self
+            .all_legal_moves()
+            .into_iter()
+            .map(|mv| {
+                let mut board = *self;
+                board.play(mv);
+                (mv, board.perft(depth - 1))
+            })
+            .collect::<Vec<_>>();
+
+        divide.sort_by_key(|(mv, _)| mv.to_string());
+        divide
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fen::FromFen;
+
+    const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+    #[test]
+    fn test_perft() {
+        let board = Board::from_fen(START);
+        assert_eq!(1, board.perft(0));
+        assert_eq!(20, board.perft(1));
+        assert_eq!(400, board.perft(2));
+        assert_eq!(8_902, board.perft(3));
+
+        let board = Board::from_fen(KIWIPETE);
+        assert_eq!(48, board.perft(1));
+        assert_eq!(2_039, board.perft(2));
+    }
+
+    #[test]
+    fn test_perft_divide() {
+        let divide = Board::from_fen(START).perft_divide(2);
+
+        assert_eq!(20, divide.len());
+        assert!(divide.iter().all(|(_, count)| *count == 20));
+        assert_eq!("a2a3", divide[0].0.to_string());
+        assert_eq!("h2h4", divide[19].0.to_string());
+
+        let divide = Board::from_fen(KIWIPETE).perft_divide(2);
+        let count = |uci: &str| {
+            divide
+                .iter()
+                .find(|(mv, _)| mv.to_string() == uci)
+                .map(|(_, count)| *count)
+        };
+
+        assert_eq!(Some(43), count("e1g1"));
+        assert_eq!(Some(43), count("e1c1"));
+        assert_eq!(Some(46), count("d5e6"));
+        assert_eq!(2_039, divide.iter().map(|(_, count)| count).sum::<u64>());
+    }
+
+    #[test]
+    fn test_perft_with_stats() {
+        assert_eq!(
+            PerftStats {
+                nodes: 8_902,
+                captures: 34,
+                en_passants: 0,
+                castles: 0,
+                promotions: 0,
+                checks: 12,
+                checkmates: 0,
+            },
+            Board::from_fen(START).perft_with_stats(3)
+        );
+
+        assert_eq!(
+            PerftStats {
+                nodes: 2_039,
+                captures: 351,
+                en_passants: 1,
+                castles: 91,
+                promotions: 0,
+                checks: 3,
+                checkmates: 0,
+            },
+            Board::from_fen(KIWIPETE).perft_with_stats(2)
+        );
+    }
+}
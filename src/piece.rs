@@ -1,6 +1,6 @@
 use crate::{color::Color, rule::MoveRule, square::Square};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum PieceType {
     Pawn,
     Rook,
@@ -10,6 +10,30 @@ pub enum PieceType {
     King,
 }
 
+/// Piece types a pawn may promote to.
+pub const PROMOTION_PIECE_TYPES: [PieceType; 4] = [
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+];
+
+impl PieceType {
+    /// Material value of the piece type in centipawns.
+    ///
+    /// The king can never be traded, so it carries no material value.
+    pub fn value(&self) -> u32 {
+        match self {
+            PieceType::Pawn => 100,
+            PieceType::Knight => 320,
+            PieceType::Bishop => 330,
+            PieceType::Rook => 500,
+            PieceType::Queen => 900,
+            PieceType::King => 0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Piece {
     piece_type: PieceType,
@@ -67,6 +91,59 @@ impl Piece {
         self.initial_square
     }
 
+    /// Index of the piece's type and color from 0 to 11: white pawn, knight, bishop, rook,
+    /// queen and king, followed by the black pieces in the same order.
+    pub fn index(&self) -> usize {
+        let type_index = match self.piece_type {
+            PieceType::Pawn => 0,
+            PieceType::Knight => 1,
+            PieceType::Bishop => 2,
+            PieceType::Rook => 3,
+            PieceType::Queen => 4,
+            PieceType::King => 5,
+        };
+
+        match self.color {
+            Color::White => type_index,
+            Color::Black => type_index + 6,
+        }
+    }
+
+    /// FEN letter of the piece, uppercase for white and lowercase for black.
+    pub fn to_ascii_char(&self) -> char {
+        let c = match self.piece_type {
+            PieceType::Pawn => 'p',
+            PieceType::Knight => 'n',
+            PieceType::Bishop => 'b',
+            PieceType::Rook => 'r',
+            PieceType::Queen => 'q',
+            PieceType::King => 'k',
+        };
+
+        match self.color {
+            Color::White => c.to_ascii_uppercase(),
+            Color::Black => c,
+        }
+    }
+
+    /// Unicode chess symbol of the piece, such as `'♔'` for a white king.
+    pub fn to_unicode_char(&self) -> char {
+        match (self.piece_type, self.color) {
+            (PieceType::King, Color::White) => '\u{2654}',
+            (PieceType::Queen, Color::White) => '\u{2655}',
+            (PieceType::Rook, Color::White) => '\u{2656}',
+            (PieceType::Bishop, Color::White) => '\u{2657}',
+            (PieceType::Knight, Color::White) => '\u{2658}',
+            (PieceType::Pawn, Color::White) => '\u{2659}',
+            (PieceType::King, Color::Black) => '\u{265A}',
+            (PieceType::Queen, Color::Black) => '\u{265B}',
+            (PieceType::Rook, Color::Black) => '\u{265C}',
+            (PieceType::Bishop, Color::Black) => '\u{265D}',
+            (PieceType::Knight, Color::Black) => '\u{265E}',
+            (PieceType::Pawn, Color::Black) => '\u{265F}',
+        }
+    }
+
     pub fn move_rules(&self) -> Vec<MoveRule> {
         match (self.piece_type, self.color) {
             (PieceType::Pawn, Color::Black) => vec![
@@ -123,9 +200,24 @@ impl Piece {
                 MoveRule::normal(1, 0),
                 MoveRule::normal(-1, 0),
                 MoveRule::castling(-2),
-                MoveRule::castling(-3),
                 MoveRule::castling(2),
             ],
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_piece_chars() {
+        let king = Piece::new(PieceType::King, Color::White, (4, 0));
+        assert_eq!('K', king.to_ascii_char());
+        assert_eq!('\u{2654}', king.to_unicode_char());
+
+        let pawn = Piece::new(PieceType::Pawn, Color::Black, (4, 6));
+        assert_eq!('p', pawn.to_ascii_char());
+        assert_eq!('\u{265F}', pawn.to_unicode_char());
+    }
+}
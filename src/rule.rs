@@ -1,4 +1,6 @@
-use crate::square::Square;
+use std::{convert::TryFrom, fmt::Display};
+
+use crate::{piece::PieceType, square::Square};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum MoveType {
@@ -7,6 +9,9 @@ pub enum MoveType {
     PawnSingleMove,
     PawnDoubleMove,
     PawnCapture,
+    /// A pawn capture onto the en passant target. Only produced by move generation,
+    /// pawn capture rules cover en passant as well.
+    EnPassant,
     Castling,
 }
 
@@ -54,3 +59,95 @@ pub struct PieceMove {
     pub move_type: MoveType,
     pub target: Square,
 }
+
+/// A move from one square to another, including the piece a pawn promotes to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Move {
+    pub from: Square,
+    pub to: Square,
+    pub promotion: Option<PieceType>,
+}
+
+impl Move {
+    pub fn new(from: impl Into<Square>, to: impl Into<Square>) -> Self {
+        Self {
+            from: from.into(),
+            to: to.into(),
+            promotion: None,
+        }
+    }
+
+    pub fn with_promotion(
+        from: impl Into<Square>,
+        to: impl Into<Square>,
+        promotion: PieceType,
+    ) -> Self {
+        Self {
+            from: from.into(),
+            to: to.into(),
+            promotion: Some(promotion),
+        }
+    }
+
+    /// Parse a move in UCI long algebraic notation, such as `e2e4` or `e7e8q`.
+    pub fn from_uci(uci: &str) -> Option<Self> {
+        if !uci.is_ascii() || (uci.len() != 4 && uci.len() != 5) {
+            return None;
+        }
+
+        let from = Square::try_from(&uci[0..2]).ok()?;
+        let to = Square::try_from(&uci[2..4]).ok()?;
+
+        let promotion = match uci.chars().nth(4) {
+            None => None,
+            Some('q') => Some(PieceType::Queen),
+            Some('r') => Some(PieceType::Rook),
+            Some('b') => Some(PieceType::Bishop),
+            Some('n') => Some(PieceType::Knight),
+            Some(_) => return None,
+        };
+
+        Some(Self {
+            from,
+            to,
+            promotion,
+        })
+    }
+}
+
+/// Formats the move in UCI long algebraic notation.
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.from, self.to)?;
+
+        match self.promotion {
+            Some(PieceType::Queen) => write!(f, "q"),
+            Some(PieceType::Rook) => write!(f, "r"),
+            Some(PieceType::Bishop) => write!(f, "b"),
+            Some(PieceType::Knight) => write!(f, "n"),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uci() {
+        let e2 = Square::try_from("e2").unwrap();
+        let e4 = Square::try_from("e4").unwrap();
+
+        assert_eq!(Some(Move::new(e2, e4)), Move::from_uci("e2e4"));
+        assert_eq!("e2e4", Move::new(e2, e4).to_string());
+
+        let promotion = Move::from_uci("a7a8n").unwrap();
+        assert_eq!(Some(PieceType::Knight), promotion.promotion);
+        assert_eq!("a7a8n", promotion.to_string());
+
+        assert_eq!(None, Move::from_uci("e2e9"));
+        assert_eq!(None, Move::from_uci("e2e4k"));
+        assert_eq!(None, Move::from_uci("e2"));
+    }
+}
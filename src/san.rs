@@ -0,0 +1,703 @@
+use std::{convert::TryFrom, fmt::Display};
+
+use crate::{
+    board::{Board, MoveError, PromotionPolicy},
+    piece::PieceType,
+    rule::{Move, MoveType},
+    square::Square,
+};
+
+/// Error when a move in Standard Algebraic Notation cannot be resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SanError {
+    /// The text is not a move in algebraic notation.
+    InvalidSyntax,
+    /// No legal move matches the notation.
+    IllegalMove,
+    /// More than one legal move matches the notation.
+    AmbiguousMove,
+    /// The starting position is not a valid FEN, see [`Board::try_from_fen`].
+    InvalidFen,
+}
+
+impl Display for SanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SanError::InvalidSyntax => write!(f, "invalid SAN syntax"),
+            SanError::IllegalMove => write!(f, "no legal move matches the SAN"),
+            SanError::AmbiguousMove => write!(f, "more than one legal move matches the SAN"),
+            SanError::InvalidFen => write!(f, "invalid FEN"),
+        }
+    }
+}
+
+impl std::error::Error for SanError {}
+
+/// Piece letters and notation accepted by [`Board::parse_san_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SanConfig {
+    pub knight: char,
+    pub bishop: char,
+    pub rook: char,
+    pub queen: char,
+    pub king: char,
+    /// Accept figurine notation, Unicode chess glyphs in place of piece letters.
+    pub figurines: bool,
+}
+
+impl SanConfig {
+    /// English piece letters N, B, R, Q and K with figurines accepted.
+    pub fn english() -> Self {
+        Self {
+            knight: 'N',
+            bishop: 'B',
+            rook: 'R',
+            queen: 'Q',
+            king: 'K',
+            figurines: true,
+        }
+    }
+
+    /// German piece letters S, L, T, D and K with figurines accepted.
+    pub fn german() -> Self {
+        Self {
+            knight: 'S',
+            bishop: 'L',
+            rook: 'T',
+            queen: 'D',
+            king: 'K',
+            figurines: true,
+        }
+    }
+
+    /// Replace the configured piece letters and figurines with English piece letters.
+    fn translate(&self, san: &str) -> String {
+        san.chars()
+            .filter_map(|c| {
+                let figurine = match c {
+                    '♔' | '♚' => Some('K'),
+                    '♕' | '♛' => Some('Q'),
+                    '♖' | '♜' => Some('R'),
+                    '♗' | '♝' => Some('B'),
+                    '♘' | '♞' => Some('N'),
+                    '♙' | '♟' => None,
+                    _ => Some(c),
+                };
+
+                if self.figurines && figurine != Some(c) {
+                    return figurine;
+                }
+
+                Some(match c {
+                    _ if c == self.knight => 'N',
+                    _ if c == self.bishop => 'B',
+                    _ if c == self.rook => 'R',
+                    _ if c == self.queen => 'Q',
+                    _ if c == self.king => 'K',
+                    _ => c,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Default for SanConfig {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+impl Board {
+    /// Standard Algebraic Notation of a legal move in the current position,
+    /// or `None` if the move is not legal.
+    pub fn move_to_san(&self, mv: &Move) -> Option<String> {
+        if !self.is_legal(mv) {
+            return None;
+        }
+
+        Some(self.legal_move_to_san(mv, &self.all_legal_moves()))
+    }
+
+    /// SAN of moving the piece on `from` to `to` in the current position, or `None` if the
+    /// move is not legal. A pawn reaching the last rank is written as promoting to a queen,
+    /// as [`Board::play_move`] would play it, or `None` if the promotion policy leaves the
+    /// promotion piece to be chosen.
+    pub fn san_for_move(&self, from: impl Into<Square>, to: impl Into<Square>) -> Option<String> {
+        let mut mv = Move::new(from, to);
+
+        if self.is_promotion(mv.from, mv.to) {
+            if self.promotion_policy() != PromotionPolicy::AutoQueen {
+                return None;
+            }
+
+            mv.promotion = Some(PieceType::Queen);
+        }
+
+        self.move_to_san(&mv)
+    }
+
+    /// Every legal move paired with its SAN. The legal moves are generated once and shared by
+    /// all moves for disambiguation, instead of once per move as with [`Board::move_to_san`].
+    pub fn all_legal_moves_annotated(&self) -> Vec<(Move, String)> {
+        let legal_moves = self.all_legal_moves();
+
+        legal_moves
+            .iter()
+            .map(|mv| (*mv, self.legal_move_to_san(mv, &legal_moves)))
+            .collect()
+    }
+
+    /// SAN of a move known to be legal, disambiguated against the given legal moves.
+    fn legal_move_to_san(&self, mv: &Move, legal_moves: &[Move]) -> String {
+        let piece = self.piece(mv.from.x, mv.from.y).unwrap();
+        let mut san = String::new();
+
+        // Castling in Chess960 positions is written as the king moving onto its own rook
+        let is_castling = piece.piece_type() == PieceType::King
+            && ((mv.to.x as i8 - mv.from.x as i8).abs() > 1
+                || self.is_occupied_by(mv.to, PieceType::Rook, piece.color()));
+
+        if is_castling {
+            san.push_str(if mv.to.x > mv.from.x { "O-O" } else { "O-O-O" });
+        } else {
+            let is_capture = self.is_capture(mv);
+
+            if piece.piece_type() == PieceType::Pawn {
+                if is_capture {
+                    san.push(file_char(mv.from.x));
+                }
+            } else {
+                san.push(piece_char(piece.piece_type()));
+                san.push_str(&self.disambiguation(mv, piece.piece_type(), legal_moves));
+            }
+
+            if is_capture {
+                san.push('x');
+            }
+
+            san.push_str(&mv.to.to_string());
+
+            if let Some(promotion) = mv.promotion {
+                san.push('=');
+                san.push(piece_char(promotion));
+            }
+        }
+
+        let mut board_after = *self;
+        board_after.play(*mv);
+
+        if board_after.is_checkmate() {
+            san.push('#');
+        } else if board_after.is_in_check() {
+            san.push('+');
+        }
+
+        san
+    }
+
+    /// Origin file, rank or square needed to tell the move apart from other legal moves of
+    /// the same piece type to the same square.
+    fn disambiguation(&self, mv: &Move, piece_type: PieceType, legal_moves: &[Move]) -> String {
+        let others = legal_moves
+            .iter()
+            .filter(|other| {
+                other.to == mv.to
+                    && other.from != mv.from
+                    && self
+                        .piece(other.from.x, other.from.y)
+                        .filter(|p| p.piece_type() == piece_type)
+                        .is_some()
+            })
+            .collect::<Vec<_>>();
+
+        if others.is_empty() {
+            String::new()
+        } else if others.iter().all(|other| other.from.x != mv.from.x) {
+            file_char(mv.from.x).to_string()
+        } else if others.iter().all(|other| other.from.y != mv.from.y) {
+            (mv.from.y + 1).to_string()
+        } else {
+            mv.from.to_string()
+        }
+    }
+
+    /// Resolve a move in Standard Algebraic Notation to a legal move in the current position.
+    ///
+    /// Check and annotation suffixes are ignored, and sloppy notation such as lowercase
+    /// piece letters, `0-0` for castling or a promotion without `=` is accepted.
+    pub fn parse_san(&self, san: &str) -> Result<Move, SanError> {
+        let san = san.trim().trim_end_matches(|c| "+#!?".contains(c));
+
+        match san {
+            "O-O" | "0-0" => return self.find_castling(true),
+            "O-O-O" | "0-0-0" => return self.find_castling(false),
+            _ => {}
+        }
+
+        let mut chars = san
+            .chars()
+            .filter(|c| !"x:-=".contains(*c))
+            .collect::<Vec<_>>();
+
+        let promotion = match chars.last().and_then(|c| piece_from_char(*c)) {
+            Some(piece_type) if chars.len() > 2 => {
+                chars.pop();
+                Some(piece_type)
+            }
+            _ => None,
+        };
+
+        if chars.len() < 2 {
+            return Err(SanError::InvalidSyntax);
+        }
+
+        let to_text = chars[chars.len() - 2..].iter().collect::<String>();
+        let to = Square::try_from(to_text.as_str()).map_err(|_| SanError::InvalidSyntax)?;
+        let prefix = &chars[..chars.len() - 2];
+
+        // A lowercase 'b' is the pawn file unless no pawn move matches, then it's a bishop.
+        let candidates = match prefix.first() {
+            Some(c) if c.is_ascii_uppercase() || *c != 'b' && piece_from_char(*c).is_some() => {
+                let piece_type = piece_from_char(*c).ok_or(SanError::InvalidSyntax)?;
+                self.matching_moves(piece_type, &prefix[1..], to, promotion)?
+            }
+            Some('b') => {
+                let moves = self.matching_moves(PieceType::Pawn, prefix, to, promotion)?;
+
+                if moves.is_empty() {
+                    self.matching_moves(PieceType::Bishop, &prefix[1..], to, promotion)?
+                } else {
+                    moves
+                }
+            }
+            _ => self.matching_moves(PieceType::Pawn, prefix, to, promotion)?,
+        };
+
+        match candidates.len() {
+            0 => Err(SanError::IllegalMove),
+            1 => Ok(candidates[0]),
+            _ => Err(SanError::AmbiguousMove),
+        }
+    }
+
+    /// Resolve a move in SAN written with the piece letters of the given configuration, such as
+    /// a localized PGN, to a legal move in the current position.
+    pub fn parse_san_with(&self, san: &str, config: &SanConfig) -> Result<Move, SanError> {
+        self.parse_san(&config.translate(san))
+    }
+
+    /// Play a move for the side to move and return its SAN, computed in the position before
+    /// the move so that capture, check and checkmate marks are correct.
+    /// Promotions need a promotion piece, so they are rejected with
+    /// [`MoveError::PromotionRequired`].
+    pub fn play_move_san_result(
+        &mut self,
+        from: impl Into<Square>,
+        to: impl Into<Square>,
+    ) -> Result<String, MoveError> {
+        let mv = Move::new(from, to);
+        let piece = self.piece_at(mv.from).ok_or(MoveError::NoPiece)?;
+
+        if piece.color() != self.active_color() {
+            return Err(MoveError::NotYourTurn);
+        }
+
+        if self.is_promotion(mv.from, mv.to) {
+            return Err(MoveError::PromotionRequired);
+        }
+
+        let san = self.move_to_san(&mv).ok_or(MoveError::IllegalMove)?;
+        self.play(mv);
+
+        Ok(san)
+    }
+
+    /// Resolve a possibly sloppy SAN move and return its canonical SAN in the current position.
+    pub fn normalize_san(&self, san: &str) -> Result<String, SanError> {
+        let mv = self.parse_san(san)?;
+        self.move_to_san(&mv).ok_or(SanError::IllegalMove)
+    }
+
+    /// The position after playing the SAN moves in order from this one, which is left
+    /// unchanged. Stops at the first move that can't be resolved and returns its error.
+    pub fn position_after_san(&self, sans: &[&str]) -> Result<Board, SanError> {
+        let mut board = *self;
+
+        for san in sans {
+            let mv = board.parse_san(san)?;
+            board.play(mv);
+        }
+
+        Ok(board)
+    }
+
+    fn find_castling(&self, kingside: bool) -> Result<Move, SanError> {
+        self.legal_moves_of_type(MoveType::Castling)
+            .into_iter()
+            .find(|mv| (mv.to.x > mv.from.x) == kingside)
+            .ok_or(SanError::IllegalMove)
+    }
+
+    fn matching_moves(
+        &self,
+        piece_type: PieceType,
+        disambiguation: &[char],
+        to: Square,
+        promotion: Option<PieceType>,
+    ) -> Result<Vec<Move>, SanError> {
+        let mut from_file = None;
+        let mut from_rank = None;
+
+        for c in disambiguation {
+            match c {
+                'a'..='h' => from_file = Some(*c as u8 - b'a'),
+                '1'..='8' => from_rank = Some(*c as u8 - b'1'),
+                _ => return Err(SanError::InvalidSyntax),
+            }
+        }
+
+        Ok(self
+            .all_legal_moves()
+            .into_iter()
+            .filter(|mv| {
+                mv.to == to
+                    && mv.promotion == promotion
+                    && from_file.filter(|&x| x != mv.from.x).is_none()
+                    && from_rank.filter(|&y| y != mv.from.y).is_none()
+                    && self
+                        .piece(mv.from.x, mv.from.y)
+                        .filter(|p| p.piece_type() == piece_type)
+                        .is_some()
+            })
+            .collect())
+    }
+}
+
+/// Convert a line of UCI moves played from the given FEN position into SAN,
+/// such as an engine's principal variation for display.
+pub fn fen_and_uci_to_san(start_fen: &str, uci_moves: &[&str]) -> Result<Vec<String>, SanError> {
+    let mut board = Board::try_from_fen(start_fen).map_err(|_| SanError::InvalidFen)?;
+
+    uci_moves
+        .iter()
+        .map(|uci| {
+            let mv = Move::from_uci(uci).ok_or(SanError::InvalidSyntax)?;
+            let san = board.move_to_san(&mv).ok_or(SanError::IllegalMove)?;
+            board.play(mv);
+
+            Ok(san)
+        })
+        .collect()
+}
+
+fn file_char(x: u8) -> char {
+    (b'a' + x) as char
+}
+
+fn piece_char(piece_type: PieceType) -> char {
+    match piece_type {
+        PieceType::Pawn => 'P',
+        PieceType::Knight => 'N',
+        PieceType::Bishop => 'B',
+        PieceType::Rook => 'R',
+        PieceType::Queen => 'Q',
+        PieceType::King => 'K',
+    }
+}
+
+fn piece_from_char(c: char) -> Option<PieceType> {
+    match c.to_ascii_uppercase() {
+        'P' => Some(PieceType::Pawn),
+        'N' => Some(PieceType::Knight),
+        'B' => Some(PieceType::Bishop),
+        'R' => Some(PieceType::Rook),
+        'Q' => Some(PieceType::Queen),
+        'K' => Some(PieceType::King),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fen::{FromFen, IntoFen};
+
+    fn square(s: &str) -> Square {
+        Square::try_from(s).unwrap()
+    }
+
+    #[test]
+    fn test_normalize_san() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+        assert_eq!(Ok("Nf3".to_string()), board.normalize_san("nf3"));
+        assert_eq!(Ok("e4".to_string()), board.normalize_san("e2-e4"));
+        assert_eq!(Err(SanError::IllegalMove), board.normalize_san("Nd4"));
+        assert_eq!(Err(SanError::InvalidSyntax), board.normalize_san("hello"));
+
+        let board = Board::from_fen("rnbqkbnr/ppp1pppp/3p4/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2");
+
+        assert_eq!(Ok("Bb5+".to_string()), board.normalize_san("bb5"));
+    }
+
+    #[test]
+    fn test_position_after_san() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let after = board.position_after_san(&["e4", "e5", "Nf3"]).unwrap();
+
+        assert_eq!(
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2",
+            after.into_fen()
+        );
+        assert_eq!(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            board.into_fen()
+        );
+
+        assert_eq!(
+            Some(SanError::IllegalMove),
+            board.position_after_san(&["e4", "e4", "Nf3"]).err()
+        );
+    }
+
+    #[test]
+    fn test_all_legal_moves_annotated() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let annotated = board.all_legal_moves_annotated();
+
+        assert_eq!(20, annotated.len());
+        assert!(annotated.contains(&(Move::new(square("g1"), square("f3")), "Nf3".to_string())));
+        assert!(annotated.contains(&(Move::new(square("e2"), square("e4")), "e4".to_string())));
+
+        for (mv, san) in annotated {
+            assert_eq!(Some(san), board.move_to_san(&mv));
+        }
+    }
+
+    #[test]
+    fn test_chess960_castling_san() {
+        let board = Board::from_fen("1r4kr/8/8/8/8/8/8/1R4KR w KQkq - 0 1");
+        let kingside = Move::new(square("g1"), square("h1"));
+        let queenside = Move::new(square("g1"), square("b1"));
+
+        assert_eq!(Some("O-O".to_string()), board.move_to_san(&kingside));
+        assert_eq!(Some("O-O-O".to_string()), board.move_to_san(&queenside));
+        assert_eq!(Ok(kingside), board.parse_san("O-O"));
+        assert_eq!(Ok(queenside), board.parse_san("O-O-O"));
+    }
+
+    #[test]
+    fn test_fen_and_uci_to_san() {
+        let fen = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+        assert_eq!(
+            Ok(vec![
+                "e4".to_string(),
+                "e5".to_string(),
+                "Nf3".to_string(),
+                "Nc6".to_string(),
+                "Bb5".to_string(),
+                "a6".to_string(),
+                "Bxc6".to_string(),
+                "dxc6".to_string(),
+                "O-O".to_string(),
+            ]),
+            fen_and_uci_to_san(
+                fen,
+                &["e2e4", "e7e5", "g1f3", "b8c6", "f1b5", "a7a6", "b5c6", "d7c6", "e1g1"]
+            )
+        );
+
+        assert_eq!(
+            Err(SanError::IllegalMove),
+            fen_and_uci_to_san(fen, &["e2e4", "e2e4"])
+        );
+        assert_eq!(
+            Err(SanError::InvalidSyntax),
+            fen_and_uci_to_san(fen, &["e2e4", "nonsense"])
+        );
+        assert_eq!(
+            Err(SanError::InvalidFen),
+            fen_and_uci_to_san("99999999999999999999 w - - 0 1", &["e2e4"])
+        );
+    }
+
+    #[test]
+    fn test_parse_san_with_config() {
+        let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let nf3 = Move::new(square("g1"), square("f3"));
+
+        assert_eq!(Ok(nf3), board.parse_san_with("Sf3", &SanConfig::german()));
+        assert_eq!(Ok(nf3), board.parse_san_with("♘f3", &SanConfig::english()));
+        assert_eq!(
+            Ok(Move::new(square("e2"), square("e4"))),
+            board.parse_san_with("e4", &SanConfig::german())
+        );
+        assert_eq!(
+            Err(SanError::InvalidSyntax),
+            board.parse_san_with(
+                "♘f3",
+                &SanConfig {
+                    figurines: false,
+                    ..SanConfig::english()
+                }
+            )
+        );
+
+        let board = Board::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+        assert_eq!(
+            Ok(Move::with_promotion(
+                square("a7"),
+                square("a8"),
+                PieceType::Queen
+            )),
+            board.parse_san_with("a8=D", &SanConfig::german())
+        );
+    }
+
+    #[test]
+    fn test_play_move_san_result() {
+        let mut board =
+            Board::from_fen("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2");
+
+        assert_eq!(
+            Ok("exd5".to_string()),
+            board.play_move_san_result(square("e4"), square("d5"))
+        );
+        assert!(board.piece_at(square("e4")).is_none());
+        assert_eq!(
+            Err(MoveError::NotYourTurn),
+            board.play_move_san_result(square("d5"), square("d6"))
+        );
+        assert_eq!(
+            Err(MoveError::NoPiece),
+            board.play_move_san_result(square("e5"), square("e4"))
+        );
+        assert_eq!(
+            Err(MoveError::IllegalMove),
+            board.play_move_san_result(square("d8"), square("d1"))
+        );
+        assert_eq!(
+            Ok("Qxd5".to_string()),
+            board.play_move_san_result(square("d8"), square("d5"))
+        );
+
+        let mut board = Board::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+        assert_eq!(
+            Err(MoveError::PromotionRequired),
+            board.play_move_san_result(square("a7"), square("a8"))
+        );
+    }
+
+    #[test]
+    fn test_en_passant_san() {
+        let board = Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1");
+        let capture = Move::new(square("e5"), square("d6"));
+
+        assert_eq!(
+            vec![capture],
+            board.legal_moves_of_type(MoveType::EnPassant)
+        );
+        assert_eq!(Some("exd6".to_string()), board.move_to_san(&capture));
+        assert_eq!(Ok(capture), board.parse_san("exd6"));
+
+        // Removing the captured pawn opens the rank for the rook
+        let board = Board::from_fen("8/8/8/k2pP2R/8/8/8/4K3 w - d6 0 1");
+        assert_eq!(Some("exd6+".to_string()), board.move_to_san(&capture));
+    }
+
+    #[test]
+    fn test_move_to_san() {
+        let board = Board::from_fen("r3k2r/8/8/3p4/4P3/8/8/R3K2R w KQkq - 0 1");
+
+        assert_eq!(
+            Some("exd5".to_string()),
+            board.move_to_san(&Move::new(square("e4"), square("d5")))
+        );
+        assert_eq!(
+            Some("O-O".to_string()),
+            board.move_to_san(&Move::new(square("e1"), square("g1")))
+        );
+        assert_eq!(
+            Some("O-O-O".to_string()),
+            board.move_to_san(&Move::new(square("e1"), square("c1")))
+        );
+        assert_eq!(
+            Some("Rxa8+".to_string()),
+            board.move_to_san(&Move::new(square("a1"), square("a8")))
+        );
+        assert_eq!(
+            None,
+            board.move_to_san(&Move::new(square("e1"), square("e3")))
+        );
+    }
+
+    #[test]
+    fn test_san_for_move() {
+        let board = Board::from_fen("rnbqkbnr/pppp1ppp/8/4p3/3P4/8/PPP1PPPP/RNBQKBNR w KQkq - 0 2");
+
+        assert_eq!(
+            Some("Nf3".to_string()),
+            board.san_for_move(square("g1"), square("f3"))
+        );
+        assert_eq!(
+            Some("dxe5".to_string()),
+            board.san_for_move(square("d4"), square("e5"))
+        );
+        assert_eq!(None, board.san_for_move(square("g1"), square("g3")));
+
+        let board = Board::from_fen("6k1/8/8/8/Q2Q4/8/8/Q3K2R w K - 0 1");
+
+        assert_eq!(
+            Some("Qa4d1".to_string()),
+            board.san_for_move(square("a4"), square("d1"))
+        );
+        assert_eq!(
+            Some("O-O".to_string()),
+            board.san_for_move(square("e1"), square("g1"))
+        );
+
+        let board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+        assert_eq!(
+            Some("Ra8#".to_string()),
+            board.san_for_move(square("a1"), square("a8"))
+        );
+
+        let mut board = Board::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+        assert_eq!(
+            Some("a8=Q+".to_string()),
+            board.san_for_move(square("a7"), square("a8"))
+        );
+
+        board.set_promotion_policy(PromotionPolicy::Prompt);
+        assert_eq!(None, board.san_for_move(square("a7"), square("a8")));
+    }
+
+    #[test]
+    fn test_san_disambiguation() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/R6R/1N2K1N1 w - - 0 1");
+
+        assert_eq!(Ok("Rad2".to_string()), board.normalize_san("Rad2"));
+        assert_eq!(Err(SanError::AmbiguousMove), board.normalize_san("Rd2"));
+        assert_eq!(Ok("Nd2".to_string()), board.normalize_san("Nd2"));
+
+        let board = Board::from_fen("4k3/8/8/8/Q2Q4/8/8/Q3K3 w - - 0 1");
+
+        assert_eq!(
+            Some("Qa4d1".to_string()),
+            board.move_to_san(&Move::new(square("a4"), square("d1")))
+        );
+    }
+
+    #[test]
+    fn test_san_promotion_and_mate() {
+        let board = Board::from_fen("7k/P7/6K1/8/8/8/8/8 w - - 0 1");
+
+        assert_eq!(
+            Move::with_promotion(square("a7"), square("a8"), PieceType::Queen),
+            board.parse_san("a8=Q").unwrap()
+        );
+        assert_eq!(Ok("a8=Q#".to_string()), board.normalize_san("a8Q"));
+        assert_eq!(Ok("a8=N".to_string()), board.normalize_san("a8=N"));
+    }
+}
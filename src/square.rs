@@ -1,11 +1,62 @@
 use std::{convert::TryFrom, fmt::Display};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+/// Number of files and ranks on the board.
+pub const BOARD_SIZE: u8 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct Square {
     pub x: u8,
     pub y: u8,
 }
 
+impl Square {
+    /// All 64 squares in a1, b1, ..., h1, a2, ..., h8 order.
+    pub fn all() -> impl Iterator<Item = Square> {
+        (0..BOARD_SIZE).flat_map(|y| (0..BOARD_SIZE).map(move |x| Square { x, y }))
+    }
+
+    /// Index of the square in a1, b1, ..., h8 order, the order of [`Square::all`]. Also the
+    /// bit of the square in a bitboard.
+    pub fn to_index(&self) -> usize {
+        (self.y * BOARD_SIZE + self.x) as usize
+    }
+
+    /// Whether the square lies within the board.
+    pub fn is_on_board(&self) -> bool {
+        self.x < BOARD_SIZE && self.y < BOARD_SIZE
+    }
+
+    /// Whether the square is on the first or last rank, where pawns promote.
+    pub fn is_back_rank(&self) -> bool {
+        self.y == 0 || self.y == BOARD_SIZE - 1
+    }
+
+    /// Number of king moves needed to get from one square to the other.
+    pub fn chebyshev_distance(&self, other: Square) -> u8 {
+        let dx = (self.x as i8 - other.x as i8).unsigned_abs();
+        let dy = (self.y as i8 - other.y as i8).unsigned_abs();
+
+        dx.max(dy)
+    }
+
+    /// The square offset by the given amount of files and ranks, or `None` if it would
+    /// be off the board.
+    pub fn offset(&self, dx: i8, dy: i8) -> Option<Square> {
+        let x = (self.x as i8).checked_add(dx)?;
+        let y = (self.y as i8).checked_add(dy)?;
+
+        if x < 0 || y < 0 {
+            return None;
+        }
+
+        Some(Square {
+            x: x as u8,
+            y: y as u8,
+        })
+        .filter(Square::is_on_board)
+    }
+}
+
 impl From<(u8, u8)> for Square {
     fn from(tuple: (u8, u8)) -> Self {
         Self {
@@ -71,3 +122,58 @@ impl TryFrom<&str> for Square {
         Ok(square)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_all_squares() {
+        let squares = Square::all().collect::<Vec<_>>();
+
+        assert_eq!(64, squares.len());
+        assert_eq!(64, squares.iter().collect::<HashSet<_>>().len());
+        assert_eq!(Square::try_from("a1"), Ok(squares[0]));
+        assert_eq!(Square::try_from("h1"), Ok(squares[7]));
+        assert_eq!(Square::try_from("h8"), Ok(squares[63]));
+
+        for (index, square) in squares.iter().enumerate() {
+            assert_eq!(index, square.to_index());
+        }
+    }
+
+    #[test]
+    fn test_is_on_board() {
+        assert!(Square { x: 0, y: 0 }.is_on_board());
+        assert!(Square { x: 7, y: 7 }.is_on_board());
+        assert!(!Square { x: 8, y: 0 }.is_on_board());
+        assert!(!Square { x: 0, y: 8 }.is_on_board());
+    }
+
+    #[test]
+    fn test_chebyshev_distance() {
+        let a1 = Square::from((0, 0));
+
+        assert_eq!(0, a1.chebyshev_distance(a1));
+        assert_eq!(7, Square::chebyshev_distance(&a1, Square::from((7, 7))));
+        assert_eq!(2, a1.chebyshev_distance(Square::from((1, 2))));
+    }
+
+    #[test]
+    fn test_offset() {
+        let a1 = Square::from((0, 0));
+
+        assert_eq!(Some(Square::from((1, 2))), a1.offset(1, 2));
+        assert_eq!(Some(Square::from((7, 7))), a1.offset(7, 7));
+        assert_eq!(Some(a1), a1.offset(0, 0));
+        assert_eq!(None, a1.offset(-1, 0));
+        assert_eq!(None, a1.offset(0, -1));
+        assert_eq!(None, a1.offset(8, 0));
+        assert_eq!(None, Square::from((7, 7)).offset(1, 1));
+        assert_eq!(None, Square::from((7, 7)).offset(i8::MAX, 0));
+        assert_eq!(None, Square::from((7, 7)).offset(0, i8::MAX));
+        assert_eq!(None, a1.offset(i8::MIN, i8::MIN));
+    }
+}
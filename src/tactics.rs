@@ -0,0 +1,638 @@
+use std::cmp::Reverse;
+
+use crate::{
+    board::{Board, MoveResult},
+    color::Color,
+    piece::PieceType,
+    rule::Move,
+    square::Square,
+};
+
+impl Board {
+    /// Whether the king of the given color stands on its back rank with every square in
+    /// front of it occupied by its own pieces, leaving no luft to escape a back-rank check.
+    pub fn has_back_rank_weakness(&self, color: Color) -> bool {
+        let (king_square, forward_y) = match self.find_all(PieceType::King, color).first() {
+            Some(square) => match color {
+                Color::White if square.y == 0 => (*square, 1),
+                Color::Black if square.y == 7 => (*square, 6),
+                _ => return false,
+            },
+            None => return false,
+        };
+
+        (king_square.x.saturating_sub(1)..=(king_square.x + 1).min(7)).all(|x| {
+            self.piece(x, forward_y)
+                .filter(|p| p.color() == color)
+                .is_some()
+        })
+    }
+
+    /// The piece pinned by the slider on `slider_square` against the king on `king_square`.
+    ///
+    /// Returns the square of the only piece standing between the two when that piece has the
+    /// king's color and the slider moves along that line: rooks along ranks and files,
+    /// bishops along diagonals and queens along both.
+    pub fn pinned_by(&self, king_square: Square, slider_square: Square) -> Option<Square> {
+        let king = (*self.piece(king_square.x, king_square.y))
+            .filter(|p| p.piece_type() == PieceType::King)?;
+        let slider = (*self.piece(slider_square.x, slider_square.y))
+            .filter(|p| p.color() != king.color())?;
+
+        let dx = king_square.x as i8 - slider_square.x as i8;
+        let dy = king_square.y as i8 - slider_square.y as i8;
+
+        let slides_on_line = match slider.piece_type() {
+            PieceType::Rook => dx == 0 || dy == 0,
+            PieceType::Bishop => dx.abs() == dy.abs(),
+            PieceType::Queen => dx == 0 || dy == 0 || dx.abs() == dy.abs(),
+            _ => false,
+        };
+
+        if !slides_on_line || (dx == 0 && dy == 0) {
+            return None;
+        }
+
+        let mut blockers = Vec::new();
+        let mut square = slider_square.offset(dx.signum(), dy.signum())?;
+
+        while square != king_square {
+            if self.piece(square.x, square.y).is_some() {
+                blockers.push(square);
+            }
+
+            square = square.offset(dx.signum(), dy.signum())?;
+        }
+
+        match blockers.as_slice() {
+            [blocker] => Some(*blocker).filter(|blocker| {
+                self.piece(blocker.x, blocker.y)
+                    .filter(|p| p.color() == king.color())
+                    .is_some()
+            }),
+            _ => None,
+        }
+    }
+
+    /// Squares of the pieces of the given color that are pinned to their king.
+    pub fn pinned_pieces(&self, color: Color) -> Vec<Square> {
+        let king_square = match self.find_all(PieceType::King, color).first() {
+            Some(square) => *square,
+            None => return Vec::new(),
+        };
+
+        Square::all()
+            .filter_map(|square| self.pinned_by(king_square, square))
+            .collect()
+    }
+
+    /// Squares of the enemy pieces forked by the piece making the move: the pieces it attacks
+    /// after the move that are either the king, worth more than the moved piece or
+    /// undefended. Empty if the move is illegal or fewer than two such pieces are attacked.
+    pub fn creates_fork(&self, mv: Move) -> Vec<Square> {
+        let mut board = *self;
+
+        if board.play(mv) == MoveResult::Invalid {
+            return Vec::new();
+        }
+
+        let piece = match board.piece_at(mv.to) {
+            Some(piece) => piece,
+            None => return Vec::new(),
+        };
+
+        let targets = board
+            .iter_pieces()
+            .filter(|(square, target)| {
+                target.color() != piece.color()
+                    && board.attacks(mv.to, *square)
+                    && (target.piece_type() == PieceType::King
+                        || target.piece_type().value() > piece.piece_type().value()
+                        || !board.is_square_attacked(*square, target.color()))
+            })
+            .map(|(square, _)| square)
+            .collect::<Vec<_>>();
+
+        if targets.len() < 2 {
+            return Vec::new();
+        }
+
+        targets
+    }
+
+    /// Whether the move lines up the sliding piece with a new pair of enemy pieces on one of
+    /// its lines, where the front piece is worth more than the one behind it. When the front
+    /// piece moves away, the piece behind it can be captured. Kings count as most valuable.
+    pub fn creates_skewer(&self, mv: Move) -> bool {
+        self.creates_line_up(mv, |front, back| front > back)
+    }
+
+    /// Whether the move lines up the sliding piece with a new pair of enemy pieces on one of
+    /// its lines, where the front piece is pinned to a more valuable piece behind it.
+    pub fn creates_pin(&self, mv: Move) -> bool {
+        self.creates_line_up(mv, |front, back| front < back)
+    }
+
+    fn creates_line_up(&self, mv: Move, is_motif: impl Fn(i32, i32) -> bool) -> bool {
+        let mut board = *self;
+
+        if board.play(mv) == MoveResult::Invalid {
+            return false;
+        }
+
+        let before = self.x_rays(mv.from);
+
+        board
+            .x_rays(mv.to)
+            .into_iter()
+            .filter(|line_up| !before.contains(line_up))
+            .any(|(front, back)| {
+                is_motif(
+                    exchange_value(board.piece_at(front).unwrap().piece_type()),
+                    exchange_value(board.piece_at(back).unwrap().piece_type()),
+                )
+            })
+    }
+
+    /// Pairs of enemy pieces standing one behind the other on the lines of the sliding piece
+    /// on the square, with nothing else between them and the slider.
+    fn x_rays(&self, slider_square: Square) -> Vec<(Square, Square)> {
+        let slider = match self.piece_at(slider_square) {
+            Some(piece) => piece,
+            None => return Vec::new(),
+        };
+
+        let directions: &[(i8, i8)] = match slider.piece_type() {
+            PieceType::Rook => &[(1, 0), (-1, 0), (0, 1), (0, -1)],
+            PieceType::Bishop => &[(1, 1), (1, -1), (-1, 1), (-1, -1)],
+            PieceType::Queen => &[
+                (1, 0),
+                (-1, 0),
+                (0, 1),
+                (0, -1),
+                (1, 1),
+                (1, -1),
+                (-1, 1),
+                (-1, -1),
+            ],
+            _ => return Vec::new(),
+        };
+
+        let first_piece = |mut square: Square, (dx, dy): (i8, i8)| loop {
+            square = square.offset(dx, dy)?;
+
+            if let Some(piece) = self.piece_at(square) {
+                return Some((square, piece));
+            }
+        };
+
+        directions
+            .iter()
+            .filter_map(|direction| {
+                let (front, front_piece) = first_piece(slider_square, *direction)?;
+                let (back, back_piece) = first_piece(front, *direction)?;
+
+                if front_piece.color() != slider.color() && back_piece.color() != slider.color() {
+                    Some((front, back))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Captures and checks the opponent could play if it were their turn, the most dangerous
+    /// first: checks before other moves, then by the value of the captured piece.
+    pub fn opponent_threats(&self) -> Vec<Move> {
+        let mut board = *self;
+        board.make_null_move();
+
+        let mut threats = board
+            .all_legal_moves()
+            .into_iter()
+            .filter_map(|mv| {
+                let mut after = board;
+                after.play(mv);
+
+                let gives_check = after.is_in_check();
+                let captured_value = if board.is_capture(&mv) {
+                    board.value_on(mv.to).unwrap_or(PieceType::Pawn.value())
+                } else {
+                    0
+                };
+
+                if gives_check || captured_value > 0 {
+                    Some((mv, gives_check, captured_value))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        threats.sort_by_key(|(_, gives_check, captured_value)| {
+            Reverse((*gives_check, *captured_value))
+        });
+        threats.into_iter().map(|(mv, _, _)| mv).collect()
+    }
+
+    /// Value in centipawns of the piece on the square, see [`PieceType::value`].
+    pub fn value_on(&self, square: impl Into<Square>) -> Option<u32> {
+        self.piece_at(square).map(|p| p.piece_type().value())
+    }
+
+    /// Squares of the pieces defending the piece on the square, i.e. the pieces of the same
+    /// color attacking it. Empty if the square is empty.
+    pub fn defenders_of(&self, square: impl Into<Square>) -> Vec<Square> {
+        let square = square.into();
+
+        match self.occupant_color(square) {
+            Some(color) => self.attackers_of(square, color),
+            None => Vec::new(),
+        }
+    }
+
+    /// Whether the piece on `from` would be defended at least as many times as it is
+    /// attacked after moving to `to`. A crude safety check that ignores piece values.
+    /// False if the move is not legal for the side to move. A pawn reaching the last rank is
+    /// taken to promote to a queen.
+    pub fn is_safe_destination(&self, from: impl Into<Square>, to: impl Into<Square>) -> bool {
+        let from = from.into();
+        let to = to.into();
+
+        let color = match self.occupant_color(from) {
+            Some(color) => color,
+            None => return false,
+        };
+
+        let mut mv = Move::new(from, to);
+
+        if self.is_promotion(from, to) {
+            mv.promotion = Some(PieceType::Queen);
+        }
+
+        let mut board = *self;
+
+        if board.play(mv) == MoveResult::Invalid {
+            return false;
+        }
+
+        board.defenders_of(to).len() >= board.attackers_of(to, color.opposite()).len()
+    }
+
+    /// Legal moves that help the piece of the side to move on the square: moves of other
+    /// pieces after which it has more defenders or fewer attackers, such as defending it,
+    /// blocking an attack on it or capturing an attacker.
+    pub fn moves_defending(&self, square: impl Into<Square>) -> Vec<Move> {
+        let square = square.into();
+        let color = self.active_color();
+
+        if self.occupant_color(square) != Some(color) {
+            return Vec::new();
+        }
+
+        let defenders = self.defenders_of(square).len();
+        let attackers = self.attackers_of(square, color.opposite()).len();
+
+        self.all_legal_moves()
+            .into_iter()
+            .filter(|mv| mv.from != square)
+            .filter(|mv| {
+                let mut board = *self;
+                board.play(*mv);
+
+                board.defenders_of(square).len() > defenders
+                    || board.attackers_of(square, color.opposite()).len() < attackers
+            })
+            .collect()
+    }
+
+    /// Pieces of the given color attacking the square, least valuable first, which is the
+    /// order they take part in an exchange. The king comes last.
+    pub fn attackers_sorted(
+        &self,
+        square: impl Into<Square>,
+        by: Color,
+    ) -> Vec<(Square, PieceType)> {
+        let mut attackers = self
+            .attackers_of(square, by)
+            .into_iter()
+            .filter_map(|from| self.piece_at(from).map(|p| (from, p.piece_type())))
+            .collect::<Vec<_>>();
+
+        attackers.sort_by_key(|(_, piece_type)| exchange_value(*piece_type));
+        attackers
+    }
+
+    /// Static exchange evaluation of the piece on the square: the material the opponent of
+    /// its owner wins by capturing it and continuing the exchange with the least valuable
+    /// attacker each time, where either side may stop recapturing when it would lose
+    /// material. The first capture is always made. 0 if the square is empty or the piece is
+    /// not attacked.
+    pub fn net_exchange_value(&self, square: impl Into<Square>) -> i32 {
+        let square = square.into();
+
+        let target = match self.piece_at(square) {
+            Some(target) => target,
+            None => return 0,
+        };
+
+        let mut board = *self;
+        let mut side = target.color().opposite();
+        let mut gains = Vec::new();
+        let mut value_on_square = exchange_value(target.piece_type());
+
+        while let Some((from, piece_type)) = board.attackers_sorted(square, side).first().copied() {
+            gains.push(value_on_square - gains.last().copied().unwrap_or(0));
+            value_on_square = exchange_value(piece_type);
+
+            *board.piece_mut(square.x, square.y) = *board.piece(from.x, from.y);
+            *board.piece_mut(from.x, from.y) = None;
+            side = side.opposite();
+        }
+
+        for i in (1..gains.len()).rev() {
+            gains[i - 1] = -(-gains[i - 1]).max(gains[i]);
+        }
+
+        gains.first().copied().unwrap_or(0)
+    }
+
+    /// A forced mate for the side to move within `depth` of its own moves, shortest first.
+    ///
+    /// The line starts with the mating side's move and alternates with the defender's
+    /// replies, choosing the reply that delays mate the longest. Draws by repetition or the
+    /// fifty-move rule are not considered.
+    pub fn find_mate(&self, depth: u32) -> Option<Vec<Move>> {
+        (1..=depth).find_map(|depth| self.mate_search(depth))
+    }
+
+    fn mate_search(&self, depth: u32) -> Option<Vec<Move>> {
+        if depth == 0 {
+            return None;
+        }
+
+        for mv in self.all_legal_moves() {
+            let mut board = *self;
+            board.play(mv);
+
+            if board.is_checkmate() {
+                return Some(vec![mv]);
+            }
+
+            let replies = board.all_legal_moves();
+
+            if depth == 1 || replies.is_empty() {
+                continue;
+            }
+
+            let mut longest: Option<Vec<Move>> = None;
+
+            for reply in replies {
+                let mut after_reply = board;
+                after_reply.play(reply);
+
+                match after_reply.mate_search(depth - 1) {
+                    Some(line) if longest.as_ref().filter(|l| l.len() > line.len()).is_none() => {
+                        longest = Some(std::iter::once(reply).chain(line).collect());
+                    }
+                    Some(_) => {}
+                    None => {
+                        longest = None;
+                        break;
+                    }
+                }
+            }
+
+            if let Some(line) = longest {
+                return Some(std::iter::once(mv).chain(line).collect());
+            }
+        }
+
+        None
+    }
+}
+
+/// Piece value used in exchanges. The king is worth more than anything it could win, so
+/// exchanges never end with the king being captured.
+fn exchange_value(piece_type: PieceType) -> i32 {
+    match piece_type {
+        PieceType::King => 100_000,
+        piece_type => piece_type.value() as i32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::*;
+    use crate::fen::FromFen;
+
+    #[test]
+    fn test_back_rank_weakness() {
+        let board = Board::from_fen("3r2k1/5ppp/8/8/8/8/5PPP/6K1 w - - 0 1");
+        assert!(board.has_back_rank_weakness(Color::White));
+        assert!(board.has_back_rank_weakness(Color::Black));
+
+        let board = Board::from_fen("3r2k1/5pp1/7p/8/8/7P/5PP1/6K1 w - - 0 1");
+        assert!(!board.has_back_rank_weakness(Color::White));
+        assert!(!board.has_back_rank_weakness(Color::Black));
+
+        let board = Board::from_fen("6k1/5ppp/8/8/8/5PPP/6K1/8 w - - 0 1");
+        assert!(!board.has_back_rank_weakness(Color::White));
+    }
+
+    #[test]
+    fn test_pinned_by() {
+        let square = |s| Square::try_from(s).unwrap();
+
+        let board = Board::from_fen("4r1k1/8/8/8/1b6/8/3N4/4K3 w - - 0 1");
+        assert_eq!(
+            Some(square("d2")),
+            board.pinned_by(square("e1"), square("b4"))
+        );
+        assert_eq!(None, board.pinned_by(square("e1"), square("e8")));
+        assert_eq!(vec![square("d2")], board.pinned_pieces(Color::White));
+
+        let board = Board::from_fen("4r1k1/8/8/8/1b6/2P5/3N4/4K3 w - - 0 1");
+        assert_eq!(None, board.pinned_by(square("e1"), square("b4")));
+        assert!(board.pinned_pieces(Color::White).is_empty());
+
+        let board = Board::from_fen("4r1k1/8/8/8/8/8/4B3/4K3 w - - 0 1");
+        assert_eq!(
+            Some(square("e2")),
+            board.pinned_by(square("e1"), square("e8"))
+        );
+
+        let board = Board::from_fen("4b1k1/8/8/8/8/8/4B3/4K3 w - - 0 1");
+        assert_eq!(None, board.pinned_by(square("e1"), square("e8")));
+    }
+
+    #[test]
+    fn test_creates_fork() {
+        let square = |s| Square::try_from(s).unwrap();
+
+        let board = Board::from_fen("4k3/8/8/1N6/2q5/8/8/4K3 w - - 0 1");
+
+        assert_eq!(
+            vec![square("c4"), square("e8")],
+            board.creates_fork(Move::new(square("b5"), square("d6")))
+        );
+        assert!(board
+            .creates_fork(Move::new(square("b5"), square("c7")))
+            .is_empty());
+        assert!(board
+            .creates_fork(Move::new(square("b5"), square("b6")))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_creates_skewer_and_pin() {
+        let square = |s| Square::try_from(s).unwrap();
+
+        let board = Board::from_fen("4q3/8/8/8/4k3/8/8/R5K1 w - - 0 1");
+        let skewer = Move::new(square("a1"), square("e1"));
+
+        assert!(board.creates_skewer(skewer));
+        assert!(!board.creates_pin(skewer));
+        assert!(!board.creates_skewer(Move::new(square("a1"), square("d1"))));
+
+        let board = Board::from_fen("8/8/4k3/8/8/4n3/8/R5K1 w - - 0 1");
+        let pin = Move::new(square("a1"), square("e1"));
+
+        assert!(board.creates_pin(pin));
+        assert!(!board.creates_skewer(pin));
+
+        // the rook already lined up the knight and the king before moving along the file
+        let board = Board::from_fen("4k3/8/8/8/4n3/8/4R3/6K1 w - - 0 1");
+        assert!(!board.creates_pin(Move::new(square("e2"), square("e1"))));
+    }
+
+    #[test]
+    fn test_opponent_threats() {
+        let square = |s| Square::try_from(s).unwrap();
+
+        let board = Board::from_fen("4k3/8/8/3b4/8/8/R5N1/4K3 w - - 0 1");
+        let threats = board.opponent_threats();
+
+        assert_eq!(Move::new(square("d5"), square("a2")), threats[0]);
+        assert!(threats.contains(&Move::new(square("d5"), square("g2"))));
+        assert!(!threats.contains(&Move::new(square("d5"), square("e6"))));
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+        assert!(board.opponent_threats().is_empty());
+    }
+
+    #[test]
+    fn test_find_mate() {
+        let square = |s| Square::try_from(s).unwrap();
+
+        let board = Board::from_fen("7k/8/6K1/8/8/8/8/R7 w - - 0 1");
+        assert_eq!(
+            Some(vec![Move::new(square("a1"), square("a8"))]),
+            board.find_mate(1)
+        );
+
+        let board = Board::from_fen("7k/8/5K2/8/8/8/8/R7 w - - 0 1");
+        assert_eq!(None, board.find_mate(1));
+
+        let line = board.find_mate(2).unwrap();
+        assert_eq!(3, line.len());
+
+        let mut after = board;
+        for mv in &line {
+            assert_eq!(MoveResult::Ok, after.play(*mv));
+        }
+        assert!(after.is_checkmate());
+
+        let board = Board::from_fen("7k/8/8/8/8/8/8/R5K1 w - - 0 1");
+        assert_eq!(None, board.find_mate(2));
+    }
+
+    #[test]
+    fn test_attackers_sorted() {
+        let square = |s| Square::try_from(s).unwrap();
+
+        let board = Board::from_fen("4k3/8/8/3r4/4P3/8/8/K2Q4 w - - 0 1");
+        assert_eq!(
+            vec![
+                (square("e4"), PieceType::Pawn),
+                (square("d1"), PieceType::Queen)
+            ],
+            board.attackers_sorted(square("d5"), Color::White)
+        );
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/1K6/Q7 w - - 0 1");
+        assert_eq!(
+            vec![
+                (square("a1"), PieceType::Queen),
+                (square("b2"), PieceType::King)
+            ],
+            board.attackers_sorted(square("a2"), Color::White)
+        );
+    }
+
+    #[test]
+    fn test_net_exchange_value() {
+        let square = |s| Square::try_from(s).unwrap();
+
+        let board = Board::from_fen("4k3/8/2p5/3n4/4P3/8/8/4K3 w - - 0 1");
+        assert_eq!(Some(320), board.value_on(square("d5")));
+        assert_eq!(None, board.value_on(square("d4")));
+        assert_eq!(vec![square("c6")], board.defenders_of(square("d5")));
+        assert_eq!(220, board.net_exchange_value(square("d5")));
+
+        // The queen wins a pawn but is lost to the recapture
+        let board = Board::from_fen("4k3/2p5/3p4/8/8/8/8/3QK3 w - - 0 1");
+        assert_eq!(-800, board.net_exchange_value(square("d6")));
+
+        // Rooks lined up behind each other take part in the exchange
+        let board = Board::from_fen("3rk3/8/8/3n4/8/8/3R4/3RK3 w - - 0 1");
+        assert_eq!(320, board.net_exchange_value(square("d5")));
+
+        let board = Board::from_fen("3rk3/3r4/8/3n4/8/8/3R4/3RK3 w - - 0 1");
+        assert_eq!(-180, board.net_exchange_value(square("d5")));
+
+        assert_eq!(0, board.net_exchange_value(square("a1")));
+    }
+
+    #[test]
+    fn test_is_safe_destination() {
+        let square = |s| Square::try_from(s).unwrap();
+
+        let board = Board::from_fen("4k3/8/2p5/8/8/8/8/3QK3 w - - 0 1");
+        assert!(!board.is_safe_destination(square("d1"), square("d5")));
+        assert!(board.is_safe_destination(square("d1"), square("d4")));
+        assert!(board.is_safe_destination(square("d1"), square("d2")));
+        assert!(!board.is_safe_destination(square("d1"), square("c3")));
+
+        let board = Board::from_fen("4k3/8/2p5/8/4P3/8/8/3QK3 w - - 0 1");
+        assert!(board.is_safe_destination(square("d1"), square("d5")));
+
+        // Not Black's turn
+        assert!(!board.is_safe_destination(square("c6"), square("c5")));
+
+        let board = Board::from_fen("4k3/8/2p5/8/4P3/8/8/3QK3 b - - 0 1");
+        assert!(board.is_safe_destination(square("c6"), square("c5")));
+        assert!(!board.is_safe_destination(square("d1"), square("d4")));
+    }
+
+    #[test]
+    fn test_moves_defending() {
+        let square = |s| Square::try_from(s).unwrap();
+
+        let board = Board::from_fen("4k3/b7/8/8/N2R4/8/8/7K w - - 0 1");
+        let mut moves = board.moves_defending(square("d4"));
+        moves.sort();
+
+        assert_eq!(
+            vec![
+                Move::new(square("a4"), square("b6")),
+                Move::new(square("a4"), square("c5")),
+            ],
+            moves
+        );
+
+        assert!(board.moves_defending(square("a7")).is_empty());
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{
+    board::Board,
+    color::Color,
+    square::{Square, BOARD_SIZE},
+};
+
+const PIECE_KEYS: [[u64; 64]; 12] = piece_keys();
+const BLACK_TO_MOVE_KEY: u64 = splitmix64(0xB1AC_C0DE).1;
+const CASTLING_KEYS: [u64; 4] = [
+    splitmix64(0xCA57_0001).1,
+    splitmix64(0xCA57_0002).1,
+    splitmix64(0xCA57_0003).1,
+    splitmix64(0xCA57_0004).1,
+];
+const EN_PASSANT_KEYS: [u64; BOARD_SIZE as usize] = en_passant_keys();
+
+/// SplitMix64 step, returning the next state and its output.
+pub(crate) const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (state, z ^ (z >> 31))
+}
+
+const fn piece_keys() -> [[u64; 64]; 12] {
+    let mut keys = [[0; 64]; 12];
+    let mut state = 0x05EE_D0FC_4E55;
+    let mut piece = 0;
+
+    while piece < 12 {
+        let mut square = 0;
+
+        while square < 64 {
+            let (next, key) = splitmix64(state);
+            state = next;
+            keys[piece][square] = key;
+            square += 1;
+        }
+
+        piece += 1;
+    }
+
+    keys
+}
+
+const fn en_passant_keys() -> [u64; BOARD_SIZE as usize] {
+    let mut keys = [0; BOARD_SIZE as usize];
+    let mut state = 0xE99A_55A9;
+    let mut file = 0;
+
+    while file < BOARD_SIZE as usize {
+        let (next, key) = splitmix64(state);
+        state = next;
+        keys[file] = key;
+        file += 1;
+    }
+
+    keys
+}
+
+impl Board {
+    /// Zobrist hash of the position: piece placement, side to move,
+    /// castling rights and en passant target. Move counters are not included.
+    pub fn zobrist_hash(&self) -> u64 {
+        self.hash_with_en_passant(self.en_passant_target())
+    }
+
+    /// Zobrist hash for detecting repetitions. Like [`Board::canonical_key`], the en passant
+    /// target is only included if a pawn can capture on it, so a position reached by a double
+    /// push repeats the same position reached without one.
+    pub fn repetition_hash(&self) -> u64 {
+        self.hash_with_en_passant(self.en_passant_legal_target())
+    }
+
+    fn hash_with_en_passant(&self, en_passant_target: Option<Square>) -> u64 {
+        let mut hash = 0;
+
+        for (square, piece) in self.iter_pieces() {
+            hash ^= PIECE_KEYS[piece.index()][square.to_index()];
+        }
+
+        if self.active_color() == Color::Black {
+            hash ^= BLACK_TO_MOVE_KEY;
+        }
+
+        let castling = [
+            self.white_castling().kingside,
+            self.white_castling().queenside,
+            self.black_castling().kingside,
+            self.black_castling().queenside,
+        ];
+
+        for (key, available) in CASTLING_KEYS.iter().zip(castling.iter()) {
+            if *available {
+                hash ^= key;
+            }
+        }
+
+        if let Some(target) = en_passant_target {
+            hash ^= EN_PASSANT_KEYS[target.x as usize];
+        }
+
+        hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fen::FromFen;
+
+    #[test]
+    fn test_zobrist_hash_transposition() {
+        let mut a = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+        let mut b = a;
+
+        a.play_move((6, 0), (5, 2));
+        a.play_move((6, 7), (5, 5));
+        a.play_move((1, 0), (2, 2));
+
+        b.play_move((1, 0), (2, 2));
+        b.play_move((6, 7), (5, 5));
+        b.play_move((6, 0), (5, 2));
+
+        assert_eq!(a.zobrist_hash(), b.zobrist_hash());
+        assert_ne!(
+            a.zobrist_hash(),
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")
+                .zobrist_hash()
+        );
+    }
+}